@@ -0,0 +1,92 @@
+//! Attributes annotate declarations with metadata, such as marking tests or deprecations.
+
+use crate::{functions::FunctionCallArgument, parser::Rule, paths::Path, Span, Spanned};
+use pest_ast::FromPest;
+use serde::Serialize;
+
+/// An Attribute is a path, optionally called with arguments, attached to the following declaration.
+///
+/// The meaning of an attribute is given by the tool which reads it; the parser only records it.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// Attribute =
+///     { "#"
+///     ~ "["
+///     ~ Path
+///     ~ ( "("
+///       ~ Closed(CommaSeparated(FunctionCallArgument)?, ")")
+///       ~ ")"
+///       )?
+///     ~ "]"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::Attribute))]
+pub struct Attribute<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    path: Path<'a>,
+    arguments: Vec<FunctionCallArgument<'a>>,
+}
+
+impl<'a> Attribute<'a> {
+    /// The path naming this attribute.
+    pub fn path(&self) -> &Path<'a> {
+        &self.path
+    }
+
+    /// The arguments given to this attribute, if any.
+    pub fn arguments(&self) -> &[FunctionCallArgument<'a>] {
+        &self.arguments
+    }
+}
+
+/// A Doc Comment documents the following declaration.
+///
+/// Line doc comments begin with exactly three slashes, and block doc comments with exactly
+/// two stars; comments such as `////` and `/***/` are ordinary comments.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// DocComment =
+///    @{ ( "///"
+///       ~ !"/"
+///       ~ ( !NEWLINE
+///         ~ ANY
+///         )*
+///       )
+///     | ( "/**"
+///       ~ !( "*" | "/" )
+///       ~ ( block_comment
+///         | ( !"*/"
+///           ~ ANY
+///           )
+///         )*
+///       ~ "*/"
+///       )
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::DocComment))]
+pub struct DocComment<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+}
+
+impl<'a> DocComment<'a> {
+    /// The documentation text, without the comment delimiters.
+    ///
+    /// Whitespace is preserved as written, including the space usually following `///`.
+    pub fn text(&self) -> &'a str {
+        let source = self.span.as_str();
+        match source.strip_prefix("///") {
+            Some(text) => text,
+            None => &source[3..source.len() - 2],
+        }
+    }
+}
@@ -1,50 +1,254 @@
 //! Containers (for lack of a better module for them) are the "branching points" of the grammar.
 
 use crate::{
+    control::{
+        BreakExpression, ContinueExpression, ForExpression, IfExpression, LoopExpression,
+        MatchExpression, ReturnExpression, WhileExpression,
+    },
     functions::{FunctionCall, FunctionDeclaration, FunctionExpression},
+    modules::{ModuleDeclaration, UseDeclaration},
     parser::Rule,
-    terminals::{Identifier, IntegerLiteral},
-    Spanned,
+    paths::Path,
+    postfix::{CallExpression, IndexExpression, MemberAccess, MethodCall, Postfix},
+    terminals::{BooleanLiteral, CharacterLiteral, Identifier, IntegerLiteral, UnitLiteral},
+    types::{EnumDeclaration, RecordExpression, TypeDeclaration},
+    Span, Spanned,
 };
+use from_pest::{pest::iterators::Pairs, ConversionError, FromPest, Void};
 use pest_ast::FromPest;
 use serde::Serialize;
 
 /// The expression is the basic unit of computation.
 ///
+/// Postfix operators are applied left to right; see [the `postfix` module](crate::postfix).
+///
 /// # Grammar
 ///
 /// ```pest,no_run
 /// Expression =
-///     { FunctionExpression
-///     | FunctionCall
-///     | Identifier
-///     | IntegerLiteral
-///     | __incomplete
+///     { ( FunctionExpression
+///       | IfExpression
+///       | MatchExpression
+///       | WhileExpression
+///       | LoopExpression
+///       | ForExpression
+///       | BreakExpression
+///       | ContinueExpression
+///       | ReturnExpression
+///       | RecordExpression
+///       | TupleExpression
+///       | ListExpression
+///       | FunctionCall
+///       | QualifiedPath
+///       | Identifier
+///       | IntegerLiteral
+///       | BooleanLiteral
+///       | CharacterLiteral
+///       | UnitLiteral
+///       | __incomplete
+///       )
+///     ~ Postfix*
 ///     }
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
-#[derive(Serialize, Spanned, FromPest)]
-#[pest_ast(rule(Rule::Expression))]
+#[derive(Serialize, Spanned)]
 #[allow(missing_docs)]
 pub enum Expression<'a> {
     FunctionExpression(FunctionExpression<'a>),
+    IfExpression(IfExpression<'a>),
+    MatchExpression(MatchExpression<'a>),
+    WhileExpression(WhileExpression<'a>),
+    LoopExpression(LoopExpression<'a>),
+    ForExpression(ForExpression<'a>),
+    BreakExpression(BreakExpression<'a>),
+    ContinueExpression(ContinueExpression<'a>),
+    ReturnExpression(ReturnExpression<'a>),
+    RecordExpression(RecordExpression<'a>),
+    TupleExpression(TupleExpression<'a>),
+    ListExpression(ListExpression<'a>),
     FunctionCall(FunctionCall<'a>),
+    Path(Path<'a>),
     Identifier(Identifier<'a>),
     IntegerLiteral(IntegerLiteral<'a>),
+    BooleanLiteral(BooleanLiteral<'a>),
+    CharacterLiteral(CharacterLiteral<'a>),
+    UnitLiteral(UnitLiteral<'a>),
+    MemberAccess(MemberAccess<'a>),
+    MethodCall(MethodCall<'a>),
+    CallExpression(CallExpression<'a>),
+    IndexExpression(IndexExpression<'a>),
+}
+
+/// Convert the operand of an expression, which has the same rule as its variant.
+macro_rules! operand {
+    ($pairs:expr; $($variant:ident),* $(,)?) => {
+        match $pairs.peek().map(|pair| pair.as_rule()) {
+            $(Some(Rule::$variant) => Expression::$variant(FromPest::from_pest($pairs)?),)*
+            _ => return Err(ConversionError::NoMatch),
+        }
+    };
+}
+
+impl<'a> FromPest<'a> for Expression<'a> {
+    type Rule = Rule;
+    type FatalError = Void;
+
+    fn from_pest(pest: &mut Pairs<'a, Rule>) -> Result<Self, ConversionError<Void>> {
+        let mut clone = pest.clone();
+        let pair = clone.next().ok_or(ConversionError::NoMatch)?;
+        // A block-like statement is an expression with fewer postfix operators,
+        // and a type argument is a path without any.
+        match pair.as_rule() {
+            Rule::Expression | Rule::BlockLikeStatement | Rule::TypeArgument => {}
+            _ => return Err(ConversionError::NoMatch),
+        }
+        let inner = &mut pair.into_inner();
+        let operand = operand!(inner;
+            FunctionExpression,
+            IfExpression,
+            MatchExpression,
+            WhileExpression,
+            LoopExpression,
+            ForExpression,
+            BreakExpression,
+            ContinueExpression,
+            ReturnExpression,
+            RecordExpression,
+            TupleExpression,
+            ListExpression,
+            FunctionCall,
+            Path,
+            Identifier,
+            IntegerLiteral,
+            BooleanLiteral,
+            CharacterLiteral,
+            UnitLiteral,
+        );
+        let this = Vec::<Postfix<'a>>::from_pest(inner)?
+            .into_iter()
+            .fold(operand, |receiver, postfix| postfix.apply(receiver));
+        if inner.peek().is_some() {
+            return Err(ConversionError::Extraneous {
+                current_node: "Expression",
+            });
+        }
+        *pest = clone;
+        Ok(this)
+    }
+}
+
+/// A tuple expression groups a fixed number of values, which may be of different types.
+///
+/// A single-element tuple requires a trailing comma; the empty tuple is the unit literal.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// TupleExpression =
+///     { "("
+///     ~ Closed(
+///         Expression
+///         ~ ","
+///         ~ CommaSeparated(Expression)?
+///       , ")")
+///     ~ ")"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::TupleExpression))]
+pub struct TupleExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    elements: Vec<Expression<'a>>,
+}
+
+impl<'a> TupleExpression<'a> {
+    /// The elements of this tuple, in order.
+    pub fn elements(&self) -> &[Expression<'a>] {
+        &self.elements
+    }
+}
+
+/// A list expression creates a list of its elements, in order.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ListExpression =
+///     { "["
+///     ~ Closed(CommaSeparated(Expression)?, "]")
+///     ~ "]"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ListExpression))]
+pub struct ListExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    elements: Vec<Expression<'a>>,
+}
+
+impl<'a> ListExpression<'a> {
+    /// The elements of this list, in order.
+    pub fn elements(&self) -> &[Expression<'a>] {
+        &self.elements
+    }
 }
 
 /// A statement is the basic unit of procedural work.
 ///
+/// Block-like expressions such as if expressions do not need a terminating semicolon,
+/// unless they end their block, where they are instead the block's tail expression.
+/// As a statement, a block-like expression ends at its closing brace: calls and indexing
+/// do not apply to it, so `if c { f() } (1, 2)` is an if statement followed by a tuple.
+/// Member accesses and method calls still do, as they cannot begin a statement.
+///
 /// # Grammar
 ///
 /// ```pest,no_run
 /// Statement =
-///     { ( Expression
+///     { ( !BlockLikeExpression
+///       ~ Expression
 ///       ~ ";"
 ///       )
+///     | ( &BlockLikeExpression
+///       ~ BlockLikeStatement
+///       ~ ( ";"
+///         | !"}"
+///         )
+///       )
 ///     | FunctionDeclaration
+///     | TypeDeclaration
+///     | EnumDeclaration
+///     | ModuleDeclaration
+///     | UseDeclaration
 ///     | __incomplete
 ///     }
+///
+/// BlockLikeStatement =
+///     { ( IfExpression
+///       | MatchExpression
+///       | WhileExpression
+///       | LoopExpression
+///       | ForExpression
+///       )
+///     ~ ( &"."
+///       ~ Postfix
+///       )*
+///     }
+///
+/// BlockLikeExpression =
+///    _{ Keyword("if")
+///     | Keyword("match")
+///     | ( Label?
+///       ~ ( Keyword("while")
+///         | Keyword("loop")
+///         | Keyword("for")
+///         )
+///       )
+///     }
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[derive(Serialize, Spanned, FromPest)]
@@ -53,4 +257,8 @@ pub enum Expression<'a> {
 pub enum Statement<'a> {
     Expression(Expression<'a>),
     FunctionDeclaration(FunctionDeclaration<'a>),
+    TypeDeclaration(TypeDeclaration<'a>),
+    EnumDeclaration(EnumDeclaration<'a>),
+    ModuleDeclaration(ModuleDeclaration<'a>),
+    UseDeclaration(UseDeclaration<'a>),
 }
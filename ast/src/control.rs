@@ -0,0 +1,573 @@
+//! Control flow expressions choose which code runs next.
+
+use crate::{
+    containers::{Expression, Statement},
+    parser::Rule,
+    patterns::Pattern,
+    terminals::Identifier,
+    Span, Spanned,
+};
+use pest_ast::FromPest;
+use serde::Serialize;
+
+/// A block is a brace-delimited sequence of statements, evaluated in place.
+///
+/// Like a function expression, the value of a block is its tail expression,
+/// or unit if there is no tail expression.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// Block =
+///     { "{"
+///     ~ Closed(
+///         Statement*
+///         ~ Expression?
+///       , "}")
+///     ~ "}"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::Block))]
+pub struct Block<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    statements: Vec<Statement<'a>>,
+    tail_expression: Option<Box<Expression<'a>>>,
+}
+
+impl<'a> Block<'a> {
+    /// The statements of this block, in order.
+    pub fn statements(&self) -> &[Statement<'a>] {
+        &self.statements
+    }
+
+    /// The expression giving the value of this block, if any.
+    pub fn tail_expression(&self) -> Option<&Expression<'a>> {
+        self.tail_expression.as_deref()
+    }
+}
+
+/// An if expression evaluates one of its branches depending on a condition.
+///
+/// The value of an if expression is the value of the branch taken.
+/// Without an else clause, the value is unit when the condition is false.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// IfExpression =
+///     { Keyword("if")
+///     ~ Closed(Expression, "{")
+///     ~ Block
+///     ~ ElseClause?
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::IfExpression))]
+pub struct IfExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__if), with(Into::into)))]
+    keyword_if: Span<'a>,
+    condition: Box<Expression<'a>>,
+    then_branch: Block<'a>,
+    else_clause: Option<Box<ElseClause<'a>>>,
+}
+
+impl<'a> IfExpression<'a> {
+    /// The condition deciding which branch is taken.
+    pub fn condition(&self) -> &Expression<'a> {
+        &self.condition
+    }
+
+    /// The branch taken when the condition is true.
+    pub fn then_branch(&self) -> &Block<'a> {
+        &self.then_branch
+    }
+
+    /// The branch taken when the condition is false, if any.
+    pub fn else_clause(&self) -> Option<&ElseClause<'a>> {
+        self.else_clause.as_deref()
+    }
+}
+
+/// An else clause is the branch of an if expression taken when the condition is false.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ElseClause =
+///     { Keyword("else")
+///     ~ ElseBranch
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ElseClause))]
+pub struct ElseClause<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__else), with(Into::into)))]
+    keyword_else: Span<'a>,
+    branch: ElseBranch<'a>,
+}
+
+impl<'a> ElseClause<'a> {
+    /// The block or chained if expression of this else clause.
+    pub fn branch(&self) -> &ElseBranch<'a> {
+        &self.branch
+    }
+}
+
+/// An else branch is either a final block or another chained if expression.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ElseBranch =
+///     { IfExpression
+///     | Block
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ElseBranch))]
+#[allow(missing_docs)]
+pub enum ElseBranch<'a> {
+    IfExpression(IfExpression<'a>),
+    Block(Block<'a>),
+}
+
+/// A match expression evaluates the first arm whose pattern matches the scrutinee.
+///
+/// The value of a match expression is the value of the arm taken.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// MatchExpression =
+///     { Keyword("match")
+///     ~ Closed(Expression, "{")
+///     ~ "{"
+///     ~ Closed(MatchArm*, "}")
+///     ~ "}"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::MatchExpression))]
+pub struct MatchExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__match), with(Into::into)))]
+    keyword_match: Span<'a>,
+    scrutinee: Box<Expression<'a>>,
+    arms: Vec<MatchArm<'a>>,
+}
+
+impl<'a> MatchExpression<'a> {
+    /// The value being matched.
+    pub fn scrutinee(&self) -> &Expression<'a> {
+        &self.scrutinee
+    }
+
+    /// The arms of this match, in order.
+    pub fn arms(&self) -> &[MatchArm<'a>] {
+        &self.arms
+    }
+}
+
+/// A match arm is a pattern and optional guard, and the code to run if they both match.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// MatchArm =
+///     { Pattern
+///     ~ MatchGuard?
+///     ~ "=>"
+///     ~ MatchArmBody
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::MatchArm))]
+pub struct MatchArm<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    pattern: Pattern<'a>,
+    guard: Option<MatchGuard<'a>>,
+    body: MatchArmBody<'a>,
+}
+
+impl<'a> MatchArm<'a> {
+    /// The pattern this arm matches.
+    pub fn pattern(&self) -> &Pattern<'a> {
+        &self.pattern
+    }
+
+    /// The guard that must also hold for this arm to be taken, if any.
+    pub fn guard(&self) -> Option<&MatchGuard<'a>> {
+        self.guard.as_ref()
+    }
+
+    /// The body evaluated when this arm is taken.
+    pub fn body(&self) -> &MatchArmBody<'a> {
+        &self.body
+    }
+}
+
+/// A match guard is an extra condition that must hold for its arm to be taken.
+///
+/// Names bound by the arm's pattern are available in the guard.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// MatchGuard =
+///     { Keyword("if")
+///     ~ Expression
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::MatchGuard))]
+pub struct MatchGuard<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__if), with(Into::into)))]
+    keyword_if: Span<'a>,
+    condition: Box<Expression<'a>>,
+}
+
+impl<'a> MatchGuard<'a> {
+    /// The condition of this guard.
+    pub fn condition(&self) -> &Expression<'a> {
+        &self.condition
+    }
+}
+
+/// The body of a match arm is either a block or a single comma-terminated expression.
+///
+/// The comma may be omitted after the last arm.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// MatchArmBody =
+///     { ( Block
+///       ~ ","?
+///       )
+///     | ( Expression
+///       ~ ( ","
+///         | &"}"
+///         )
+///       )
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::MatchArmBody))]
+#[allow(missing_docs)]
+pub enum MatchArmBody<'a> {
+    Block(Block<'a>),
+    Expression(Expression<'a>),
+}
+
+/// A while expression repeatedly evaluates its body as long as its condition holds.
+///
+/// The value of a while expression is unit.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// WhileExpression =
+///     { Label?
+///     ~ Keyword("while")
+///     ~ Closed(Expression, "{")
+///     ~ Block
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::WhileExpression))]
+pub struct WhileExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    label: Option<Label<'a>>,
+    #[pest_ast(inner(rule(Rule::Keyword__while), with(Into::into)))]
+    keyword_while: Span<'a>,
+    condition: Box<Expression<'a>>,
+    body: Block<'a>,
+}
+
+impl<'a> WhileExpression<'a> {
+    /// The label of this loop, if any.
+    pub fn label(&self) -> Option<&Label<'a>> {
+        self.label.as_ref()
+    }
+
+    /// The condition checked before each iteration.
+    pub fn condition(&self) -> &Expression<'a> {
+        &self.condition
+    }
+
+    /// The body of this loop.
+    pub fn body(&self) -> &Block<'a> {
+        &self.body
+    }
+}
+
+/// A loop expression evaluates its body forever, until it is exited with `break`.
+///
+/// The value of a loop expression is the value given to the `break` that exits it.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// LoopExpression =
+///     { Label?
+///     ~ Keyword("loop")
+///     ~ Block
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::LoopExpression))]
+pub struct LoopExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    label: Option<Label<'a>>,
+    #[pest_ast(inner(rule(Rule::Keyword__loop), with(Into::into)))]
+    keyword_loop: Span<'a>,
+    body: Block<'a>,
+}
+
+impl<'a> LoopExpression<'a> {
+    /// The label of this loop, if any.
+    pub fn label(&self) -> Option<&Label<'a>> {
+        self.label.as_ref()
+    }
+
+    /// The body of this loop.
+    pub fn body(&self) -> &Block<'a> {
+        &self.body
+    }
+}
+
+/// A for expression evaluates its body once for each element of an iterable value.
+///
+/// Each element is matched against the pattern. The value of a for expression is unit.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ForExpression =
+///     { Label?
+///     ~ Keyword("for")
+///     ~ Pattern
+///     ~ Keyword("in")
+///     ~ Closed(Expression, "{")
+///     ~ Block
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ForExpression))]
+pub struct ForExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    label: Option<Label<'a>>,
+    #[pest_ast(inner(rule(Rule::Keyword__for), with(Into::into)))]
+    keyword_for: Span<'a>,
+    pattern: Pattern<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__in), with(Into::into)))]
+    keyword_in: Span<'a>,
+    iterable: Box<Expression<'a>>,
+    body: Block<'a>,
+}
+
+impl<'a> ForExpression<'a> {
+    /// The label of this loop, if any.
+    pub fn label(&self) -> Option<&Label<'a>> {
+        self.label.as_ref()
+    }
+
+    /// The pattern bound to each element.
+    pub fn pattern(&self) -> &Pattern<'a> {
+        &self.pattern
+    }
+
+    /// The value iterated over.
+    pub fn iterable(&self) -> &Expression<'a> {
+        &self.iterable
+    }
+
+    /// The body of this loop.
+    pub fn body(&self) -> &Block<'a> {
+        &self.body
+    }
+}
+
+/// A break expression exits a loop, optionally with a value for a `loop` expression.
+///
+/// Without a label, the innermost enclosing loop is exited.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// BreakExpression =
+///     { Keyword("break")
+///     ~ LabelReference?
+///     ~ Expression?
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::BreakExpression))]
+pub struct BreakExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__break), with(Into::into)))]
+    keyword_break: Span<'a>,
+    label: Option<LabelReference<'a>>,
+    value: Option<Box<Expression<'a>>>,
+}
+
+impl<'a> BreakExpression<'a> {
+    /// The label of the loop to exit, if given.
+    pub fn label(&self) -> Option<&LabelReference<'a>> {
+        self.label.as_ref()
+    }
+
+    /// The value to give the loop, if any.
+    pub fn value(&self) -> Option<&Expression<'a>> {
+        self.value.as_deref()
+    }
+}
+
+/// A continue expression skips the rest of a loop body, starting the next iteration.
+///
+/// Without a label, the innermost enclosing loop is continued.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ContinueExpression =
+///     { Keyword("continue")
+///     ~ LabelReference?
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ContinueExpression))]
+pub struct ContinueExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__continue), with(Into::into)))]
+    keyword_continue: Span<'a>,
+    label: Option<LabelReference<'a>>,
+}
+
+impl<'a> ContinueExpression<'a> {
+    /// The label of the loop to continue, if given.
+    pub fn label(&self) -> Option<&LabelReference<'a>> {
+        self.label.as_ref()
+    }
+}
+
+/// A return expression exits a function with a value, or unit if no value is given.
+///
+/// Without a label, this exits the innermost enclosing function declaration
+/// (or the outermost function expression), even from within a closure nested in it.
+/// With a label, the labelled function expression is exited instead.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ReturnExpression =
+///     { Keyword("return")
+///     ~ LabelReference?
+///     ~ Expression?
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ReturnExpression))]
+pub struct ReturnExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__return), with(Into::into)))]
+    keyword_return: Span<'a>,
+    label: Option<LabelReference<'a>>,
+    value: Option<Box<Expression<'a>>>,
+}
+
+impl<'a> ReturnExpression<'a> {
+    /// The label of the function to exit, if given.
+    pub fn label(&self) -> Option<&LabelReference<'a>> {
+        self.label.as_ref()
+    }
+
+    /// The value to return, if any.
+    pub fn value(&self) -> Option<&Expression<'a>> {
+        self.value.as_deref()
+    }
+}
+
+/// A label names a loop or function expression, so that it can be exited from within.
+///
+/// A trailing closure without a label is implicitly labelled with the name of the called function.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// Label =
+///    ${ Identifier
+///     ~ "@"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::Label))]
+pub struct Label<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    name: Identifier<'a>,
+}
+
+impl<'a> Label<'a> {
+    /// The name of this label.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+}
+
+/// A label reference names the loop or function expression that control flow applies to.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// LabelReference =
+///    ${ "@"
+///     ~ Identifier
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::LabelReference))]
+pub struct LabelReference<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    name: Identifier<'a>,
+}
+
+impl<'a> LabelReference<'a> {
+    /// The name of the referenced label.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+}
@@ -1,10 +1,14 @@
 //! Functions are units of behavior. They take input, do some work, and produce output.
 
 use crate::{
+    attributes::{Attribute, DocComment},
     containers::{Expression, Statement},
+    control::Label,
     parser::Rule,
     paths::Path,
+    patterns::Pattern,
     terminals::Identifier,
+    types::GenericParameter,
     Span, Spanned,
 };
 use pest_ast::FromPest;
@@ -16,12 +20,15 @@ use serde::Serialize;
 ///
 /// ```pest,no_run
 /// FunctionExpression =
-///     { "{"
-///     ~ ( CommaSeparated(FunctionExpressionArgument)?
-///       ~ "->"
-///       )?
-///     ~ Statement*
-///     ~ Expression?
+///     { Label?
+///     ~ "{"
+///     ~ Closed(
+///         ( CommaSeparated(FunctionExpressionArgument)?
+///         ~ "->"
+///         )?
+///         ~ Statement*
+///         ~ Expression?
+///       , "}")
 ///     ~ "}"
 ///     }
 /// ```
@@ -31,18 +38,44 @@ use serde::Serialize;
 pub struct FunctionExpression<'a> {
     #[pest_ast(outer(with(Into::into)))]
     span: Span<'a>,
+    label: Option<Label<'a>>,
     arguments: Vec<FunctionExpressionArgument<'a>>,
     statements: Vec<Statement<'a>>,
     tail_expression: Option<Box<Expression<'a>>>,
 }
 
+impl<'a> FunctionExpression<'a> {
+    /// The label of this function expression, if any.
+    pub fn label(&self) -> Option<&Label<'a>> {
+        self.label.as_ref()
+    }
+
+    /// The arguments of this function expression.
+    pub fn arguments(&self) -> &[FunctionExpressionArgument<'a>] {
+        &self.arguments
+    }
+
+    /// The statements of the body, in order.
+    pub fn statements(&self) -> &[Statement<'a>] {
+        &self.statements
+    }
+
+    /// The expression giving the value of the body, if any.
+    pub fn tail_expression(&self) -> Option<&Expression<'a>> {
+        self.tail_expression.as_deref()
+    }
+}
+
 /// Function Expression arguments are the bindings for inputs to the function.
 ///
+/// An argument may destructure its input with a tuple or record pattern, such as
+/// `{ (a, b) -> a }`. Function expressions are always called with positional arguments.
+///
 /// # Grammar
 ///
 /// ```pest,no_run
 /// FunctionExpressionArgument =
-///     { Identifier
+///     { Pattern
 ///     ~ TypeAscription?
 ///     }
 /// ```
@@ -52,21 +85,46 @@ pub struct FunctionExpression<'a> {
 pub struct FunctionExpressionArgument<'a> {
     #[pest_ast(outer(with(Into::into)))]
     span: Span<'a>,
-    name: Identifier<'a>,
+    pattern: Pattern<'a>,
     r#type: Option<Box<Path<'a>>>,
 }
 
+impl<'a> FunctionExpressionArgument<'a> {
+    /// The pattern binding this argument.
+    pub fn pattern(&self) -> &Pattern<'a> {
+        &self.pattern
+    }
+
+    /// The type of this argument, if given.
+    pub fn r#type(&self) -> Option<&Path<'a>> {
+        self.r#type.as_deref()
+    }
+}
+
 /// A Function Call is an invocation of a function, passing arguments to produce work and output.
 ///
+/// The function is named by a path. To call the value of any other expression,
+/// see [`CallExpression`](crate::postfix::CallExpression).
+///
+/// Brackets after the last segment of the path index its value rather than give type arguments,
+/// so `fs[0](x)` calls the first element of `fs`.
+///
 /// # Grammar
 ///
 /// ```pest,no_run
 /// FunctionCall =
-///     { Identifier
+///     { !( "::"?
+///        ~ ( PathSegment
+///          ~ "::"
+///          )*
+///        ~ Identifier
+///        ~ "["
+///        )
+///     ~ Path
 ///     ~ "("
-///     ~ CommaSeparated(FunctionCallArgument)?
+///     ~ Closed(CommaSeparated(FunctionCallArgument)?, ")")
 ///     ~ ")"
-///     ~ FunctionExpression?
+///     ~ TrailingClosure?
 ///     }
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -75,13 +133,42 @@ pub struct FunctionExpressionArgument<'a> {
 pub struct FunctionCall<'a> {
     #[pest_ast(outer(with(Into::into)))]
     span: Span<'a>,
-    path: Identifier<'a>,
+    path: Path<'a>,
     arguments: Vec<FunctionCallArgument<'a>>,
     tail_closure: Option<Box<FunctionExpression<'a>>>,
 }
 
+impl<'a> FunctionCall<'a> {
+    /// The path naming the called function.
+    pub fn path(&self) -> &Path<'a> {
+        &self.path
+    }
+
+    /// The arguments within the parentheses.
+    pub fn arguments(&self) -> &[FunctionCallArgument<'a>] {
+        &self.arguments
+    }
+
+    /// The trailing closure, if any.
+    pub fn tail_closure(&self) -> Option<&FunctionExpression<'a>> {
+        self.tail_closure.as_deref()
+    }
+}
+
 /// Function Call Arguments bind a function argument to some value expression.
 ///
+/// The arguments of a call are matched to the arguments of a function declaration as follows:
+///
+/// - Positional arguments bind the declared arguments in order,
+///   and must come before all named arguments.
+/// - Keyword-only arguments, after a `*` or a variadic argument, are never bound positionally.
+///   A variadic argument instead binds a list of the positional arguments left over, if any.
+/// - A named argument (`name = value`) binds the declared argument labelled `name`.
+///   Each declared argument may be bound at most once, and a variadic argument not at all.
+/// - A trailing closure binds the last declared argument.
+/// - Every declared argument left unbound takes its default value,
+///   and must have one if it is not bound.
+///
 /// # Grammar
 ///
 /// ```pest,no_run
@@ -102,16 +189,44 @@ pub struct FunctionCallArgument<'a> {
     value: Box<Expression<'a>>,
 }
 
+impl<'a> FunctionCallArgument<'a> {
+    /// The name of the argument bound, if given.
+    pub fn name(&self) -> Option<&Identifier<'a>> {
+        self.name.as_ref()
+    }
+
+    /// The value of this argument.
+    pub fn value(&self) -> &Expression<'a> {
+        &self.value
+    }
+}
+
 /// A Function Declaration creates a new argument-overloadable function binding.
 ///
+/// A function declaration may be generic over types, which are in scope in its signature and body.
+///
 /// # Grammar
 ///
 /// ```pest,no_run
 /// FunctionDeclaration =
-///     { Keyword("function")
+///     { DocComment*
+///     ~ Attribute*
+///     ~ Keyword("function")
 ///     ~ Identifier
+///     ~ ( "["
+///       ~ CommaSeparated(GenericParameter)
+///       ~ "]"
+///       )?
 ///     ~ "("
-///     ~ CommaSeparated(FunctionDeclarationArgument)?
+///     ~ ( ( ( Separated(FunctionDeclarationArgument, ",")
+///           ~ ( ","
+///             ~ KeywordOnlyArguments
+///             )?
+///           )
+///         | KeywordOnlyArguments
+///         )
+///       ~ ","?
+///       )?
 ///     ~ ")"
 ///     ~ TypeAscription?
 ///     ~ "="
@@ -124,22 +239,82 @@ pub struct FunctionCallArgument<'a> {
 pub struct FunctionDeclaration<'a> {
     #[pest_ast(outer(with(Into::into)))]
     span: Span<'a>,
+    docs: Vec<DocComment<'a>>,
+    attributes: Vec<Attribute<'a>>,
     #[pest_ast(inner(rule(Rule::Keyword__function), with(Into::into)))]
     keyword_function: Span<'a>,
     name: Identifier<'a>,
+    generics: Vec<GenericParameter<'a>>,
     arguments: Vec<FunctionDeclarationArgument<'a>>,
+    keyword_only: Option<KeywordOnlyArguments<'a>>,
     r#return: Option<Box<Path<'a>>>,
     body: Box<FunctionExpression<'a>>,
 }
 
+impl<'a> FunctionDeclaration<'a> {
+    /// The doc comments of this declaration.
+    pub fn docs(&self) -> &[DocComment<'a>] {
+        &self.docs
+    }
+
+    /// The attributes of this declaration.
+    pub fn attributes(&self) -> &[Attribute<'a>] {
+        &self.attributes
+    }
+
+    /// The name of the declared function.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+
+    /// The generic type parameters of this function.
+    pub fn generics(&self) -> &[GenericParameter<'a>] {
+        &self.generics
+    }
+
+    /// The arguments of this function, before any keyword-only arguments.
+    pub fn arguments(&self) -> &[FunctionDeclarationArgument<'a>] {
+        &self.arguments
+    }
+
+    /// The keyword-only arguments of this function, if it has a `*` marker.
+    pub fn keyword_only(&self) -> Option<&KeywordOnlyArguments<'a>> {
+        self.keyword_only.as_ref()
+    }
+
+    /// The return type of this function, if given.
+    pub fn r#return(&self) -> Option<&Path<'a>> {
+        self.r#return.as_deref()
+    }
+
+    /// The body of this function.
+    pub fn body(&self) -> &FunctionExpression<'a> {
+        &self.body
+    }
+}
+
 /// Function Declaration Arguments are the types and optional labels used to call the function.
 ///
+/// A labelled argument may have a default value, used when a call does not bind it.
+/// The default is evaluated at each such call.
+///
+/// A variadic argument (`...args: List[T]`) collects every remaining positional argument
+/// of a call into a list. A function may have at most one, and the arguments after it
+/// are keyword-only.
+///
 /// # Grammar
 ///
 /// ```pest,no_run
 /// FunctionDeclarationArgument =
-///     { ( Identifier
+///     { ( Variadic
+///       ~ Identifier
+///       ~ TypeAscription
+///       )
+///     | ( Identifier
 ///       ~ TypeAscription
+///       ~ ( "="
+///         ~ Expression
+///         )?
 ///       )
 ///     | Path
 ///     }
@@ -150,6 +325,74 @@ pub struct FunctionDeclaration<'a> {
 pub struct FunctionDeclarationArgument<'a> {
     #[pest_ast(outer(with(Into::into)))]
     span: Span<'a>,
+    variadic: Option<Variadic<'a>>,
     name: Option<Identifier<'a>>,
     r#type: Box<Path<'a>>,
+    default: Option<Box<Expression<'a>>>,
+}
+
+impl<'a> FunctionDeclarationArgument<'a> {
+    /// The `...` marking this argument as variadic, if present.
+    pub fn variadic(&self) -> Option<&Variadic<'a>> {
+        self.variadic.as_ref()
+    }
+
+    /// The label of this argument, if it has one.
+    pub fn name(&self) -> Option<&Identifier<'a>> {
+        self.name.as_ref()
+    }
+
+    /// The type of this argument.
+    pub fn r#type(&self) -> &Path<'a> {
+        &self.r#type
+    }
+
+    /// The default value of this argument, if any.
+    pub fn default(&self) -> Option<&Expression<'a>> {
+        self.default.as_deref()
+    }
+}
+
+/// The `...` marking a variadic function declaration argument.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// Variadic = { "..." }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::Variadic))]
+pub struct Variadic<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+}
+
+/// Keyword-only arguments follow a `*` in a function declaration,
+/// and can only be bound by named arguments at a call.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// KeywordOnlyArguments =
+///     { "*"
+///     ~ ( ","
+///       ~ Separated(FunctionDeclarationArgument, ",")
+///       )?
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::KeywordOnlyArguments))]
+pub struct KeywordOnlyArguments<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    arguments: Vec<FunctionDeclarationArgument<'a>>,
+}
+
+impl<'a> KeywordOnlyArguments<'a> {
+    /// The keyword-only arguments.
+    pub fn arguments(&self) -> &[FunctionDeclarationArgument<'a>] {
+        &self.arguments
+    }
 }
@@ -59,6 +59,20 @@
 //!     ~ Path
 //!     }
 //!
+//! // The parser keeps a stack of the tokens that close each enclosing context.
+//! // A trailing closure may not begin where its `{` would close the context instead,
+//! // such as in the condition of an if expression, which is closed by its block.
+//! Closed(Rule, Close) =
+//!    _{ PUSH_LITERAL(Close)
+//!     ~ Rule
+//!     ~ DROP
+//!     }
+//!
+//! TrailingClosure =
+//!    _{ !PEEK[-1..]
+//!     ~ FunctionExpression
+//!     }
+//!
 //! __incomplete = // unmatchable, serves to mark incomplete choices
 //!    _{ !ANY
 //!     ~ ANY
@@ -68,9 +82,12 @@
 //!    _{ WHITE_SPACE
 //!     }
 //!
+//! // Doc comments are not skipped; they are part of the declaration they document.
 //! COMMENT =
-//!    _{ line_comment
-//!     | block_comment
+//!    _{ !DocComment
+//!     ~ ( line_comment
+//!       | block_comment
+//!       )
 //!     }
 //!
 //! line_comment =
@@ -93,10 +110,16 @@
 
 #![warn(missing_docs)]
 
+pub mod attributes;
 pub mod containers;
+pub mod control;
 pub mod functions;
+pub mod modules;
 pub mod paths;
+pub mod patterns;
+pub mod postfix;
 pub mod terminals;
+pub mod types;
 
 #[doc(hidden)]
 pub mod parser {
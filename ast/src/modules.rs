@@ -0,0 +1,251 @@
+//! Modules organize declarations into namespaces, which may span several source files.
+
+use crate::{
+    attributes::{Attribute, DocComment},
+    containers::Statement,
+    parser::Rule,
+    paths::Path,
+    terminals::Identifier,
+    Span, Spanned,
+};
+use pest_ast::FromPest;
+use serde::Serialize;
+
+/// A Module File is the contents of a source file, which is the body of a module.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ModuleFile =
+///     { SOI
+///     ~ Statement*
+///     ~ EOI
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ModuleFile))]
+pub struct ModuleFile<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    statements: Vec<Statement<'a>>,
+    #[serde(skip)]
+    eoi: EndOfInput,
+}
+
+impl<'a> ModuleFile<'a> {
+    /// The statements of this file, in order.
+    pub fn statements(&self) -> &[Statement<'a>] {
+        &self.statements
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, FromPest)]
+#[pest_ast(rule(Rule::EOI))]
+struct EndOfInput;
+
+/// A Module Declaration creates a new named module.
+///
+/// Without a body, the module's contents are loaded from the file `name.nafi`,
+/// in a directory named after the declaring module (or beside the root file).
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ModuleDeclaration =
+///     { DocComment*
+///     ~ Attribute*
+///     ~ Keyword("module")
+///     ~ Identifier
+///     ~ ( ModuleBody
+///       | ";"
+///       )
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ModuleDeclaration))]
+pub struct ModuleDeclaration<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    docs: Vec<DocComment<'a>>,
+    attributes: Vec<Attribute<'a>>,
+    #[pest_ast(inner(rule(Rule::Keyword__module), with(Into::into)))]
+    keyword_module: Span<'a>,
+    name: Identifier<'a>,
+    body: Option<ModuleBody<'a>>,
+}
+
+impl<'a> ModuleDeclaration<'a> {
+    /// The doc comments of this declaration.
+    pub fn docs(&self) -> &[DocComment<'a>] {
+        &self.docs
+    }
+
+    /// The attributes of this declaration.
+    pub fn attributes(&self) -> &[Attribute<'a>] {
+        &self.attributes
+    }
+
+    /// The name of the declared module.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+
+    /// The inline body of this module, or `None` if it is loaded from a file.
+    pub fn body(&self) -> Option<&ModuleBody<'a>> {
+        self.body.as_ref()
+    }
+}
+
+/// A Module Body is the inline contents of a module.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ModuleBody =
+///     { "{"
+///     ~ Closed(Statement*, "}")
+///     ~ "}"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ModuleBody))]
+pub struct ModuleBody<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    statements: Vec<Statement<'a>>,
+}
+
+impl<'a> ModuleBody<'a> {
+    /// The statements of this module, in order.
+    pub fn statements(&self) -> &[Statement<'a>] {
+        &self.statements
+    }
+}
+
+/// A Use Declaration brings names from other modules into scope.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// UseDeclaration =
+///     { Keyword("use")
+///     ~ UseTree
+///     ~ ";"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::UseDeclaration))]
+pub struct UseDeclaration<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__use), with(Into::into)))]
+    keyword_use: Span<'a>,
+    tree: UseTree<'a>,
+}
+
+impl<'a> UseDeclaration<'a> {
+    /// The tree of paths imported.
+    pub fn tree(&self) -> &UseTree<'a> {
+        &self.tree
+    }
+}
+
+/// A Use Tree is a path to import, optionally renamed, or a group of use trees under a path.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// UseTree =
+///     { Path
+///     ~ ( UseGroup
+///       | UseAlias
+///       )?
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::UseTree))]
+pub struct UseTree<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    path: Path<'a>,
+    group: Option<UseGroup<'a>>,
+    alias: Option<UseAlias<'a>>,
+}
+
+impl<'a> UseTree<'a> {
+    /// The path of this tree.
+    pub fn path(&self) -> &Path<'a> {
+        &self.path
+    }
+
+    /// The trees imported relative to the path, if any.
+    pub fn group(&self) -> Option<&UseGroup<'a>> {
+        self.group.as_ref()
+    }
+
+    /// The name the path is imported as, if renamed.
+    pub fn alias(&self) -> Option<&UseAlias<'a>> {
+        self.alias.as_ref()
+    }
+}
+
+/// A Use Group imports several use trees relative to the same path.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// UseGroup =
+///     { "::"
+///     ~ "{"
+///     ~ CommaSeparated(UseTree)?
+///     ~ "}"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::UseGroup))]
+pub struct UseGroup<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    trees: Vec<UseTree<'a>>,
+}
+
+impl<'a> UseGroup<'a> {
+    /// The trees of this group.
+    pub fn trees(&self) -> &[UseTree<'a>] {
+        &self.trees
+    }
+}
+
+/// A Use Alias imports a path under a different name.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// UseAlias =
+///     { Keyword("as")
+///     ~ Identifier
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::UseAlias))]
+pub struct UseAlias<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    #[pest_ast(inner(rule(Rule::Keyword__as), with(Into::into)))]
+    keyword_as: Span<'a>,
+    name: Identifier<'a>,
+}
+
+impl<'a> UseAlias<'a> {
+    /// The name imported as.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+}
@@ -6,6 +6,8 @@ use serde::Serialize;
 
 /// A Path is a sequence of `::`-delimited segments referring to a type or data place.
 ///
+/// Where a path would be ambiguous with a single identifier, a qualified path is used instead.
+///
 /// # Grammar
 ///
 /// ```pest,no_run
@@ -13,6 +15,15 @@ use serde::Serialize;
 ///     { "::"?
 ///     ~ Separated(PathSegment, "::")
 ///     }
+///
+/// QualifiedPath =
+///    _{ &( "::"
+///        | ( PathSegment
+///          ~ "::"
+///          )
+///        )
+///     ~ Path
+///     }
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[derive(Serialize, Spanned, FromPest)]
@@ -23,19 +34,41 @@ pub struct Path<'a> {
     segments: Vec<PathSegment<'a>>,
 }
 
+impl<'a> Path<'a> {
+    /// The segments of this path, in order.
+    pub fn segments(&self) -> &[PathSegment<'a>] {
+        &self.segments
+    }
+}
+
 /// A Path Segment uniquely refers to a type, data place, or module.
 /// It takes arguments for parameterized types and modules.
 ///
+/// An argument that is a whole path is parsed as one, so that it may take arguments itself,
+/// as in `List[Tuple[Int, Int]]`. Other arguments are parsed as expressions.
+///
 /// # Grammar
 ///
 /// ```pest,no_run
 /// PathSegment =
 ///     { Identifier
 ///     ~ ( "["
-///       ~ CommaSeparated(Expression)
+///       ~ Closed(CommaSeparated(PathSegmentArgument), "]")
 ///       ~ "]"
 ///       )?
 ///     }
+///
+/// PathSegmentArgument =
+///    _{ TypeArgument
+///     | Expression
+///     }
+///
+/// TypeArgument =
+///     { Path
+///     ~ &( ","
+///        | "]"
+///        )
+///     }
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[derive(Serialize, Spanned, FromPest)]
@@ -46,3 +79,15 @@ pub struct PathSegment<'a> {
     name: Identifier<'a>,
     arguments: Vec<Expression<'a>>,
 }
+
+impl<'a> PathSegment<'a> {
+    /// The name of this segment.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+
+    /// The bracketed arguments of this segment.
+    pub fn arguments(&self) -> &[Expression<'a>] {
+        &self.arguments
+    }
+}
@@ -0,0 +1,199 @@
+//! Patterns describe the shape of a value, and bind names to the parts of it.
+
+use crate::{
+    parser::Rule,
+    paths::Path,
+    terminals::{BooleanLiteral, CharacterLiteral, Identifier, IntegerLiteral, UnitLiteral},
+    Span, Spanned,
+};
+use pest_ast::FromPest;
+use serde::Serialize;
+
+/// A pattern is matched against a value, and either fails or binds its names.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// Pattern =
+///     { WildcardPattern
+///     | TuplePattern
+///     | IntegerLiteral
+///     | BooleanLiteral
+///     | CharacterLiteral
+///     | UnitLiteral
+///     | RecordPattern
+///     | ConstructorPattern
+///     | Identifier
+///     | __incomplete
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::Pattern))]
+#[allow(missing_docs)]
+pub enum Pattern<'a> {
+    Wildcard(WildcardPattern<'a>),
+    Tuple(TuplePattern<'a>),
+    IntegerLiteral(IntegerLiteral<'a>),
+    BooleanLiteral(BooleanLiteral<'a>),
+    CharacterLiteral(CharacterLiteral<'a>),
+    UnitLiteral(UnitLiteral<'a>),
+    Record(RecordPattern<'a>),
+    Constructor(ConstructorPattern<'a>),
+    Binding(Identifier<'a>),
+}
+
+/// The wildcard pattern matches any value without binding it.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// WildcardPattern = @{ "_" ~ !XID_CONTINUE }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::WildcardPattern))]
+pub struct WildcardPattern<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+}
+
+/// A tuple pattern matches each element of a tuple against a pattern.
+///
+/// A single-element tuple pattern requires a trailing comma.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// TuplePattern =
+///     { "("
+///     ~ Pattern
+///     ~ ","
+///     ~ CommaSeparated(Pattern)?
+///     ~ ")"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::TuplePattern))]
+pub struct TuplePattern<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    elements: Vec<Pattern<'a>>,
+}
+
+impl<'a> TuplePattern<'a> {
+    /// The patterns of the elements, in order.
+    pub fn elements(&self) -> &[Pattern<'a>] {
+        &self.elements
+    }
+}
+
+/// A constructor pattern matches one variant of an enum, and each of its fields against a pattern.
+///
+/// The parentheses may be omitted to match a variant without fields by its qualified path.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// ConstructorPattern =
+///     { ( Path
+///       ~ "("
+///       ~ CommaSeparated(Pattern)?
+///       ~ ")"
+///       )
+///     | QualifiedPath
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::ConstructorPattern))]
+pub struct ConstructorPattern<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    path: Path<'a>,
+    fields: Vec<Pattern<'a>>,
+}
+
+impl<'a> ConstructorPattern<'a> {
+    /// The path naming the enum variant.
+    pub fn path(&self) -> &Path<'a> {
+        &self.path
+    }
+
+    /// The patterns of the fields, in order.
+    pub fn fields(&self) -> &[Pattern<'a>] {
+        &self.fields
+    }
+}
+
+/// A record pattern matches the fields of a record by name.
+///
+/// A field without a pattern binds the field's value to its name, so `Point { x, y }`
+/// is shorthand for `Point { x = x, y = y }`. Fields not named are ignored.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// RecordPattern =
+///     { Path
+///     ~ "{"
+///     ~ CommaSeparated(RecordFieldPattern)?
+///     ~ "}"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::RecordPattern))]
+pub struct RecordPattern<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    path: Path<'a>,
+    fields: Vec<RecordFieldPattern<'a>>,
+}
+
+impl<'a> RecordPattern<'a> {
+    /// The path naming the record type.
+    pub fn path(&self) -> &Path<'a> {
+        &self.path
+    }
+
+    /// The field patterns.
+    pub fn fields(&self) -> &[RecordFieldPattern<'a>] {
+        &self.fields
+    }
+}
+
+/// A record field pattern matches one field of a record against a pattern.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// RecordFieldPattern =
+///     { Identifier
+///     ~ ( "="
+///       ~ Pattern
+///       )?
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::RecordFieldPattern))]
+pub struct RecordFieldPattern<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    name: Identifier<'a>,
+    pattern: Option<Pattern<'a>>,
+}
+
+impl<'a> RecordFieldPattern<'a> {
+    /// The name of the field.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+
+    /// The pattern matching the field, or `None` to bind it to its name.
+    pub fn pattern(&self) -> Option<&Pattern<'a>> {
+        self.pattern.as_ref()
+    }
+}
@@ -0,0 +1,258 @@
+//! Postfix expressions apply an operation to the expression before them.
+//!
+//! Postfix expressions are left-recursive, which the parser cannot handle directly.
+//! Instead, an expression is matched as an operand followed by any number of postfix operators,
+//! which are then applied from left to right to build the tree.
+
+use crate::{
+    containers::Expression,
+    functions::{FunctionCallArgument, FunctionExpression},
+    parser::Rule,
+    terminals::Identifier,
+    Span, Spanned,
+};
+use pest_ast::FromPest;
+use serde::Serialize;
+
+/// A Member Access reads a field of a record.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// // MemberAccess = { Expression ~ MemberAccessPostfix }
+/// MemberAccessPostfix =
+///     { "."
+///     ~ Identifier
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned)]
+pub struct MemberAccess<'a> {
+    span: Span<'a>,
+    receiver: Box<Expression<'a>>,
+    member: Identifier<'a>,
+}
+
+impl<'a> MemberAccess<'a> {
+    /// The expression whose field is read.
+    pub fn receiver(&self) -> &Expression<'a> {
+        &self.receiver
+    }
+
+    /// The name of the field.
+    pub fn member(&self) -> &Identifier<'a> {
+        &self.member
+    }
+}
+
+/// A Method Call invokes a method of a value, passing arguments like a function call.
+///
+/// If the only argument is a trailing closure, the parentheses may be omitted.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// // MethodCall = { Expression ~ MethodCallPostfix }
+/// MethodCallPostfix =
+///     { "."
+///     ~ Identifier
+///     ~ ( ( "("
+///         ~ Closed(CommaSeparated(FunctionCallArgument)?, ")")
+///         ~ ")"
+///         ~ TrailingClosure?
+///         )
+///       | TrailingClosure
+///       )
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned)]
+pub struct MethodCall<'a> {
+    span: Span<'a>,
+    receiver: Box<Expression<'a>>,
+    method: Identifier<'a>,
+    arguments: Vec<FunctionCallArgument<'a>>,
+    tail_closure: Option<Box<FunctionExpression<'a>>>,
+}
+
+impl<'a> MethodCall<'a> {
+    /// The expression whose method is called.
+    pub fn receiver(&self) -> &Expression<'a> {
+        &self.receiver
+    }
+
+    /// The name of the method.
+    pub fn method(&self) -> &Identifier<'a> {
+        &self.method
+    }
+
+    /// The arguments within the parentheses.
+    pub fn arguments(&self) -> &[FunctionCallArgument<'a>] {
+        &self.arguments
+    }
+
+    /// The trailing closure, if any.
+    pub fn tail_closure(&self) -> Option<&FunctionExpression<'a>> {
+        self.tail_closure.as_deref()
+    }
+}
+
+/// A Call Expression invokes the function that an expression evaluates to.
+///
+/// A call of a function named by a path is instead a [`FunctionCall`](crate::functions::FunctionCall).
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// // CallExpression = { Expression ~ CallPostfix }
+/// CallPostfix =
+///     { "("
+///     ~ Closed(CommaSeparated(FunctionCallArgument)?, ")")
+///     ~ ")"
+///     ~ TrailingClosure?
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned)]
+pub struct CallExpression<'a> {
+    span: Span<'a>,
+    callee: Box<Expression<'a>>,
+    arguments: Vec<FunctionCallArgument<'a>>,
+    tail_closure: Option<Box<FunctionExpression<'a>>>,
+}
+
+impl<'a> CallExpression<'a> {
+    /// The expression evaluating to the called function.
+    pub fn callee(&self) -> &Expression<'a> {
+        &self.callee
+    }
+
+    /// The arguments within the parentheses.
+    pub fn arguments(&self) -> &[FunctionCallArgument<'a>] {
+        &self.arguments
+    }
+
+    /// The trailing closure, if any.
+    pub fn tail_closure(&self) -> Option<&FunctionExpression<'a>> {
+        self.tail_closure.as_deref()
+    }
+}
+
+/// An Index Expression reads an element of a collection, such as a list.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// // IndexExpression = { Expression ~ IndexPostfix }
+/// IndexPostfix =
+///     { "["
+///     ~ Closed(Expression, "]")
+///     ~ "]"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned)]
+pub struct IndexExpression<'a> {
+    span: Span<'a>,
+    receiver: Box<Expression<'a>>,
+    index: Box<Expression<'a>>,
+}
+
+impl<'a> IndexExpression<'a> {
+    /// The expression being indexed.
+    pub fn receiver(&self) -> &Expression<'a> {
+        &self.receiver
+    }
+
+    /// The index.
+    pub fn index(&self) -> &Expression<'a> {
+        &self.index
+    }
+}
+
+/// A postfix operator, not yet applied to its receiver.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// Postfix =
+///     { MethodCallPostfix
+///     | MemberAccessPostfix
+///     | CallPostfix
+///     | IndexPostfix
+///     }
+/// ```
+#[derive(FromPest)]
+#[pest_ast(rule(Rule::Postfix))]
+pub(crate) enum Postfix<'a> {
+    MethodCall(MethodCallPostfix<'a>),
+    MemberAccess(MemberAccessPostfix<'a>),
+    Call(CallPostfix<'a>),
+    Index(IndexPostfix<'a>),
+}
+
+#[derive(FromPest)]
+#[pest_ast(rule(Rule::MemberAccessPostfix))]
+pub(crate) struct MemberAccessPostfix<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    member: Identifier<'a>,
+}
+
+#[derive(FromPest)]
+#[pest_ast(rule(Rule::MethodCallPostfix))]
+pub(crate) struct MethodCallPostfix<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    method: Identifier<'a>,
+    arguments: Vec<FunctionCallArgument<'a>>,
+    tail_closure: Option<Box<FunctionExpression<'a>>>,
+}
+
+#[derive(FromPest)]
+#[pest_ast(rule(Rule::CallPostfix))]
+pub(crate) struct CallPostfix<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    arguments: Vec<FunctionCallArgument<'a>>,
+    tail_closure: Option<Box<FunctionExpression<'a>>>,
+}
+
+#[derive(FromPest)]
+#[pest_ast(rule(Rule::IndexPostfix))]
+pub(crate) struct IndexPostfix<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    index: Box<Expression<'a>>,
+}
+
+impl<'a> Postfix<'a> {
+    /// Apply this postfix operator to the expression it follows.
+    pub(crate) fn apply(self, receiver: Expression<'a>) -> Expression<'a> {
+        match self {
+            Postfix::MethodCall(postfix) => Expression::MethodCall(MethodCall {
+                span: postfix.span.starting_at(receiver.span().start_byte()),
+                receiver: Box::new(receiver),
+                method: postfix.method,
+                arguments: postfix.arguments,
+                tail_closure: postfix.tail_closure,
+            }),
+            Postfix::MemberAccess(postfix) => Expression::MemberAccess(MemberAccess {
+                span: postfix.span.starting_at(receiver.span().start_byte()),
+                receiver: Box::new(receiver),
+                member: postfix.member,
+            }),
+            Postfix::Call(postfix) => Expression::CallExpression(CallExpression {
+                span: postfix.span.starting_at(receiver.span().start_byte()),
+                callee: Box::new(receiver),
+                arguments: postfix.arguments,
+                tail_closure: postfix.tail_closure,
+            }),
+            Postfix::Index(postfix) => Expression::IndexExpression(IndexExpression {
+                span: postfix.span.starting_at(receiver.span().start_byte()),
+                receiver: Box::new(receiver),
+                index: postfix.index,
+            }),
+        }
+    }
+}
@@ -87,6 +87,15 @@ impl<'a> Span<'a> {
     }
 }
 
+/// Combinators
+impl<'a> Span<'a> {
+    /// Extend this span backwards to start at an earlier byte offset in the same source.
+    pub(crate) fn starting_at(self, start_byte: u32) -> Self {
+        debug_assert!(start_byte <= self.start_byte);
+        Span { start_byte, ..self }
+    }
+}
+
 impl<'a> From<::pest::Span<'a>> for Span<'a> {
     fn from(span: ::pest::Span<'a>) -> Self {
         if span.end() <= u32::MAX as usize {
@@ -9,8 +9,31 @@ use serde::Serialize;
 /// # Grammar
 ///
 /// ```pest,no_run
-/// // UAX31-R1 Default Identifier grammar
-/// Identifier = @{ XID_START ~ XID_CONTINUE* }
+/// // UAX31-R1 Default Identifier grammar, excluding reserved words
+/// Identifier = @{ !ReservedWord ~ XID_START ~ XID_CONTINUE* }
+///
+/// ReservedWord =
+///    @{ ( "function"
+///       | "true"
+///       | "false"
+///       | "if"
+///       | "else"
+///       | "match"
+///       | "while"
+///       | "loop"
+///       | "for"
+///       | "in"
+///       | "break"
+///       | "continue"
+///       | "return"
+///       | "type"
+///       | "enum"
+///       | "module"
+///       | "use"
+///       | "as"
+///       )
+///     ~ !XID_CONTINUE
+///     }
 /// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[derive(Serialize, Spanned, FromPest)]
@@ -20,6 +43,13 @@ pub struct Identifier<'a> {
     span: Span<'a>,
 }
 
+impl<'a> Identifier<'a> {
+    /// The name this identifier spells.
+    pub fn as_str(&self) -> &'a str {
+        self.span.as_str()
+    }
+}
+
 /// An integer in the source code.
 ///
 /// # Grammar
@@ -34,3 +64,115 @@ pub struct IntegerLiteral<'a> {
     #[pest_ast(outer(with(Into::into)))]
     span: Span<'a>,
 }
+
+impl<'a> IntegerLiteral<'a> {
+    /// The decimal digits of this literal.
+    ///
+    /// Integer literals are not limited in size, so this is left to the consumer to interpret.
+    pub fn digits(&self) -> &'a str {
+        self.span.as_str()
+    }
+}
+
+/// A boolean in the source code.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// BooleanLiteral =
+///    @{ Keyword("true")
+///     | Keyword("false")
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::BooleanLiteral))]
+pub struct BooleanLiteral<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+}
+
+impl<'a> BooleanLiteral<'a> {
+    /// The truth value this literal represents.
+    pub fn value(&self) -> bool {
+        self.span.as_str() == "true"
+    }
+}
+
+/// The unit value, the single value of the empty tuple type.
+///
+/// There is only one unit value, so the literal carries no information beyond its span.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// UnitLiteral =
+///     { "("
+///     ~ ")"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::UnitLiteral))]
+pub struct UnitLiteral<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+}
+
+/// A single Unicode scalar value in the source code.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// CharacterLiteral =
+///    @{ "'"
+///     ~ ( ( "\\"
+///         ~ CharacterEscape
+///         )
+///       | ( !( "'" | "\\" | NEWLINE )
+///         ~ ANY
+///         )
+///       )
+///     ~ "'"
+///     }
+///
+/// CharacterEscape =
+///    _{ ( "u{"
+///       ~ ASCII_HEX_DIGIT{1, 6}
+///       ~ "}"
+///       )
+///     | "n" | "r" | "t" | "0" | "\\" | "'" | "\""
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::CharacterLiteral))]
+pub struct CharacterLiteral<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+}
+
+impl<'a> CharacterLiteral<'a> {
+    /// The character this literal represents, with any escape applied.
+    ///
+    /// This is `None` only if a `\u{..}` escape does not name a Unicode scalar value,
+    /// such as a surrogate code point or a value above `10FFFF`.
+    pub fn value(&self) -> Option<char> {
+        let source = self.span.as_str();
+        let inner = &source[1..source.len() - 1];
+        let mut chars = inner.chars();
+        match chars.next()? {
+            '\\' => match chars.next()? {
+                'n' => Some('\n'),
+                'r' => Some('\r'),
+                't' => Some('\t'),
+                '0' => Some('\0'),
+                'u' => u32::from_str_radix(&inner[3..inner.len() - 1], 16)
+                    .ok()
+                    .and_then(std::char::from_u32),
+                escaped => Some(escaped),
+            },
+            c => Some(c),
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Types describe the shape of data, and what can be done with it.
+
+use crate::{
+    attributes::{Attribute, DocComment},
+    containers::Expression,
+    parser::Rule,
+    paths::Path,
+    terminals::Identifier,
+    Span, Spanned,
+};
+use pest_ast::FromPest;
+use serde::Serialize;
+
+/// A Type Declaration creates a new named type.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// TypeDeclaration =
+///     { DocComment*
+///     ~ Attribute*
+///     ~ Keyword("type")
+///     ~ Identifier
+///     ~ "="
+///     ~ RecordType
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::TypeDeclaration))]
+pub struct TypeDeclaration<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    docs: Vec<DocComment<'a>>,
+    attributes: Vec<Attribute<'a>>,
+    #[pest_ast(inner(rule(Rule::Keyword__type), with(Into::into)))]
+    keyword_type: Span<'a>,
+    name: Identifier<'a>,
+    definition: RecordType<'a>,
+}
+
+impl<'a> TypeDeclaration<'a> {
+    /// The doc comments of this declaration.
+    pub fn docs(&self) -> &[DocComment<'a>] {
+        &self.docs
+    }
+
+    /// The attributes of this declaration.
+    pub fn attributes(&self) -> &[Attribute<'a>] {
+        &self.attributes
+    }
+
+    /// The name of the declared type.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+
+    /// The record type defining the type.
+    pub fn definition(&self) -> &RecordType<'a> {
+        &self.definition
+    }
+}
+
+/// A Record Type is a product of named fields.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// RecordType =
+///     { "{"
+///     ~ CommaSeparated(RecordField)?
+///     ~ "}"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::RecordType))]
+pub struct RecordType<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    fields: Vec<RecordField<'a>>,
+}
+
+impl<'a> RecordType<'a> {
+    /// The fields of this record type.
+    pub fn fields(&self) -> &[RecordField<'a>] {
+        &self.fields
+    }
+}
+
+/// A Record Field is a named, typed part of a record.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// RecordField =
+///     { Identifier
+///     ~ TypeAscription
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::RecordField))]
+pub struct RecordField<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    name: Identifier<'a>,
+    r#type: Box<Path<'a>>,
+}
+
+impl<'a> RecordField<'a> {
+    /// The name of this field.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+
+    /// The type of this field.
+    pub fn r#type(&self) -> &Path<'a> {
+        &self.r#type
+    }
+}
+
+/// A Record Expression constructs a value of a record type from a value for each field.
+///
+/// Like a trailing closure, a record expression may not begin where its `{` would close the context.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// RecordExpression =
+///     { Path
+///     ~ !PEEK[-1..]
+///     ~ "{"
+///     ~ Closed(CommaSeparated(RecordFieldInitializer)?, "}")
+///     ~ "}"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::RecordExpression))]
+pub struct RecordExpression<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    r#type: Box<Path<'a>>,
+    fields: Vec<RecordFieldInitializer<'a>>,
+}
+
+impl<'a> RecordExpression<'a> {
+    /// The path naming the record type.
+    pub fn r#type(&self) -> &Path<'a> {
+        &self.r#type
+    }
+
+    /// The field initializers.
+    pub fn fields(&self) -> &[RecordFieldInitializer<'a>] {
+        &self.fields
+    }
+}
+
+/// A Record Field Initializer gives the value of one field in a record expression.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// RecordFieldInitializer =
+///     { Identifier
+///     ~ "="
+///     ~ Expression
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::RecordFieldInitializer))]
+pub struct RecordFieldInitializer<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    name: Identifier<'a>,
+    value: Box<Expression<'a>>,
+}
+
+impl<'a> RecordFieldInitializer<'a> {
+    /// The name of the field.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+
+    /// The value of the field.
+    pub fn value(&self) -> &Expression<'a> {
+        &self.value
+    }
+}
+
+/// An Enum Declaration creates a new tagged union type, which is exactly one of its variants.
+///
+/// Each variant is reachable through the enum's path, such as `Shape::Circle`,
+/// as a function constructing that variant from its fields (or the value itself, without fields).
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// EnumDeclaration =
+///     { DocComment*
+///     ~ Attribute*
+///     ~ Keyword("enum")
+///     ~ Identifier
+///     ~ "{"
+///     ~ CommaSeparated(EnumVariant)?
+///     ~ "}"
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::EnumDeclaration))]
+pub struct EnumDeclaration<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    docs: Vec<DocComment<'a>>,
+    attributes: Vec<Attribute<'a>>,
+    #[pest_ast(inner(rule(Rule::Keyword__enum), with(Into::into)))]
+    keyword_enum: Span<'a>,
+    name: Identifier<'a>,
+    variants: Vec<EnumVariant<'a>>,
+}
+
+impl<'a> EnumDeclaration<'a> {
+    /// The doc comments of this declaration.
+    pub fn docs(&self) -> &[DocComment<'a>] {
+        &self.docs
+    }
+
+    /// The attributes of this declaration.
+    pub fn attributes(&self) -> &[Attribute<'a>] {
+        &self.attributes
+    }
+
+    /// The name of the declared enum.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+
+    /// The variants of this enum.
+    pub fn variants(&self) -> &[EnumVariant<'a>] {
+        &self.variants
+    }
+}
+
+/// An Enum Variant is one case of an enum, with its own named fields.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// EnumVariant =
+///     { Identifier
+///     ~ ( "("
+///       ~ CommaSeparated(RecordField)?
+///       ~ ")"
+///       )?
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::EnumVariant))]
+pub struct EnumVariant<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    name: Identifier<'a>,
+    fields: Vec<RecordField<'a>>,
+}
+
+impl<'a> EnumVariant<'a> {
+    /// The name of this variant.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+
+    /// The fields of this variant.
+    pub fn fields(&self) -> &[RecordField<'a>] {
+        &self.fields
+    }
+}
+
+/// A Generic Parameter names a type that is supplied where the generic item is used.
+///
+/// # Grammar
+///
+/// ```pest,no_run
+/// GenericParameter =
+///     { Identifier
+///     }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Spanned, FromPest)]
+#[pest_ast(rule(Rule::GenericParameter))]
+pub struct GenericParameter<'a> {
+    #[pest_ast(outer(with(Into::into)))]
+    span: Span<'a>,
+    name: Identifier<'a>,
+}
+
+impl<'a> GenericParameter<'a> {
+    /// The name of this type parameter.
+    pub fn name(&self) -> &Identifier<'a> {
+        &self.name
+    }
+}
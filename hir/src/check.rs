@@ -0,0 +1,1389 @@
+//! Type checking, which infers the type of every expression and reports those that disagree.
+//!
+//! Types are inferred locally, within the body of each function:
+//!
+//! - The parameters of a function declaration have the types written in its signature.
+//!   Its return type is the one written, or else inferred from its body.
+//! - The parameters of a closure without a written type are inferred from the parameter
+//!   the closure is passed to, or else from how they are used within the closure.
+//! - The overload a call selects is chosen by the types of its arguments as far as they
+//!   are known when the call is reached (see [`overload`](crate::overload)).
+//!   Closure arguments are checked once the overload is selected,
+//!   so their parameters get the types the overload expects.
+//! - The generic type parameters of a function are inferred afresh for each call.
+//!   Within the function itself they stand for a type that is not known, and only
+//!   agree with themselves.
+//! - `break`, `continue`, and `return` never produce a value, so they have every type.
+//!
+//! Once every type is inferred, each `match` must cover every value of its scrutinee,
+//! and an arm whose pattern only matches values that earlier arms match is reported
+//! as unreachable. Arms with a guard do not count toward either, as the guard may not hold.
+//!
+//! A type that is still not known once its function is checked is left [unknown](Ty::Unknown).
+
+use crate::{
+    diagnostics::Diagnostic,
+    expressions::{Argument, Arm, Block, Closure, Expr, ExprKind, Literal, Stmt},
+    items::{Function, Item, Module, ParameterKind, Program},
+    overload::{self, CallArgument, CallSite, Overloads, ParameterBinding, Signature},
+    paths::Path,
+    patterns::{Pattern, PatternKind},
+    resolve::{Res, Resolutions},
+    types::Ty,
+    visit::{self, Visitor},
+    HirId, Span,
+};
+use std::collections::{HashMap, HashSet};
+
+/// The types of the expressions and bindings of a program, and the overloads its calls select.
+#[derive(Clone, Debug, Default)]
+pub struct Typing {
+    pub(crate) exprs: HashMap<HirId, Ty>,
+    pub(crate) bindings: HashMap<HirId, Ty>,
+    pub(crate) overloads: Overloads,
+}
+
+impl Typing {
+    /// The type of an expression.
+    pub fn expr(&self, expr: &Expr) -> &Ty {
+        self.exprs.get(&expr.id).unwrap_or(&Ty::Unknown)
+    }
+
+    /// The type of the value a binding pattern binds.
+    pub fn binding(&self, pattern: &Pattern) -> &Ty {
+        self.bindings.get(&pattern.id).unwrap_or(&Ty::Unknown)
+    }
+
+    /// The overload each call of a function declaration selects.
+    pub fn overloads(&self) -> &Overloads {
+        &self.overloads
+    }
+}
+
+/// Infer the type of every expression of the program, and check that they agree.
+///
+/// This also selects the overload of every call, with [`overload::select`].
+pub fn check(program: &Program, resolutions: &Resolutions) -> (Typing, Vec<Diagnostic>) {
+    let mut checker = Checker {
+        resolutions,
+        functions: HashMap::new(),
+        records: HashMap::new(),
+        variants: HashMap::new(),
+        enums: HashMap::new(),
+        variables: vec![],
+        targets: HashMap::new(),
+        checked: HashSet::new(),
+        matches: vec![],
+        typing: Typing::default(),
+        diagnostics: vec![],
+    };
+    visit::walk_program(&mut checker, program);
+    checker.module(&program.prelude);
+    checker.module(&program.root);
+    checker.infer(&program.main);
+    for (scrutinee, arms, ty) in std::mem::take(&mut checker.matches) {
+        let ty = checker.finish(&ty);
+        checker.arms(scrutinee, arms, &ty);
+    }
+
+    let mut typing = std::mem::take(&mut checker.typing);
+    for ty in typing
+        .exprs
+        .values_mut()
+        .chain(typing.bindings.values_mut())
+    {
+        *ty = checker.finish(ty);
+    }
+    (typing, checker.diagnostics)
+}
+
+/// The signature of a function declaration.
+struct FunctionInfo<'h> {
+    signature: Signature<'h>,
+    r#return: Ty,
+}
+
+/// A record type, or an enum variant, by the types of its fields.
+struct Fields {
+    /// The type the record type or variant constructs.
+    ty: Ty,
+    /// How the record type or variant is named in diagnostics.
+    name: String,
+    fields: Vec<(String, Ty)>,
+}
+
+impl Fields {
+    fn get(&self, name: &str) -> Option<&Ty> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, ty)| ty)
+    }
+}
+
+/// The callee of a call, as the checker sees it.
+enum Callee {
+    /// Function declarations to select an overload from, by their name.
+    Overloads(Vec<HirId>, String),
+    /// An enum variant with fields.
+    Variant(HirId),
+    /// Any other value, by its type.
+    Value(Ty),
+}
+
+struct Checker<'h, 'r> {
+    resolutions: &'r Resolutions,
+    functions: HashMap<HirId, FunctionInfo<'h>>,
+    records: HashMap<HirId, Fields>,
+    variants: HashMap<HirId, Fields>,
+    /// The variants of each enum, in order.
+    enums: HashMap<HirId, Vec<HirId>>,
+    /// The type each inference variable stands for, once it is known.
+    variables: Vec<Option<Ty>>,
+    /// The type of the value each loop, closure, and function declaration produces,
+    /// for the `break`s and `return`s that exit them.
+    targets: HashMap<HirId, Ty>,
+    /// The function declarations whose bodies are checked, or being checked.
+    checked: HashSet<HirId>,
+    /// The scrutinee and arms of each `match`, and the type of the scrutinee,
+    /// whose arms are checked once every type is inferred.
+    matches: Vec<(&'h Expr, &'h [Arm], Ty)>,
+    typing: Typing,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Collects the signatures of function declarations and the fields of types.
+impl<'h, 'r> Visitor<'h> for Checker<'h, 'r> {
+    fn visit_item(&mut self, item: &'h Item) {
+        match item {
+            Item::Type(r#type) => {
+                let fields = Fields {
+                    ty: Ty::Named(r#type.id, r#type.name.text.clone()),
+                    name: r#type.name.text.clone(),
+                    fields: r#type
+                        .fields
+                        .iter()
+                        .map(|field| (field.name.text.clone(), self.ty(&field.ty)))
+                        .collect(),
+                };
+                self.records.insert(r#type.id, fields);
+            }
+            Item::Enum(r#enum) => {
+                for variant in &r#enum.variants {
+                    let fields = Fields {
+                        ty: Ty::Named(r#enum.id, r#enum.name.text.clone()),
+                        name: format!("{}::{}", r#enum.name.text, variant.name.text),
+                        fields: variant
+                            .fields
+                            .iter()
+                            .map(|field| (field.name.text.clone(), self.ty(&field.ty)))
+                            .collect(),
+                    };
+                    self.variants.insert(variant.id, fields);
+                }
+                let variants = r#enum.variants.iter().map(|variant| variant.id).collect();
+                self.enums.insert(r#enum.id, variants);
+            }
+            _ => visit::walk_item(self, item),
+        }
+    }
+
+    fn visit_function(&mut self, function: &'h Function) {
+        let mut parameter_types = vec![];
+        for parameter in &function.parameters {
+            let ty = self.ty(&parameter.ty);
+            if parameter.kind == ParameterKind::Variadic {
+                match ty {
+                    Ty::List(_) | Ty::Unknown => {}
+                    _ => {
+                        let message = format!(
+                            "a variadic parameter collects its arguments into a `List`, \
+                         so its type must be a `List`, not `{}`",
+                            ty,
+                        );
+                        self.error(parameter.ty.span, message);
+                    }
+                }
+            }
+            parameter_types.push(ty);
+        }
+        let r#return = match &function.return_type {
+            Some(path) => self.ty(path),
+            None => self.fresh(),
+        };
+        let signature = Signature {
+            function,
+            parameter_types,
+        };
+        self.functions.insert(
+            function.id,
+            FunctionInfo {
+                signature,
+                r#return,
+            },
+        );
+        visit::walk_function(self, function);
+    }
+}
+
+impl<'h, 'r> Checker<'h, 'r> {
+    fn error(&mut self, span: Span, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic::error(span, message));
+    }
+
+    /// The type a path written as a type refers to.
+    fn ty(&mut self, path: &Path) -> Ty {
+        match Ty::from_path(path, self.resolutions) {
+            Ok(ty) => ty,
+            Err(diagnostic) => {
+                self.diagnostics.push(diagnostic);
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn res(&self, path: &Path) -> &'r Res {
+        self.resolutions.get(path).unwrap_or(&Res::Err)
+    }
+
+    // Inference variables
+
+    /// A new type yet to be inferred.
+    fn fresh(&mut self) -> Ty {
+        self.variables.push(None);
+        Ty::Infer(self.variables.len() as u32 - 1)
+    }
+
+    /// The type with its outermost inference variables replaced by what they stand for.
+    fn shallow(&self, ty: &Ty) -> Ty {
+        let mut ty = ty.clone();
+        while let Ty::Infer(variable) = ty {
+            match &self.variables[variable as usize] {
+                Some(known) => ty = known.clone(),
+                None => break,
+            }
+        }
+        ty
+    }
+
+    /// The type with every inference variable replaced by what it stands for, if known.
+    fn resolve(&self, ty: &Ty) -> Ty {
+        match self.shallow(ty) {
+            Ty::List(element) => Ty::List(Box::new(self.resolve(&element))),
+            Ty::Tuple(elements) => Ty::Tuple(elements.iter().map(|ty| self.resolve(ty)).collect()),
+            Ty::Function(parameters, r#return) => Ty::Function(
+                parameters.iter().map(|ty| self.resolve(ty)).collect(),
+                Box::new(self.resolve(&r#return)),
+            ),
+            ty => ty,
+        }
+    }
+
+    /// The type as it appears in the results: types that are not known are unknown.
+    fn finish(&self, ty: &Ty) -> Ty {
+        match self.resolve(ty) {
+            Ty::Infer(_) => Ty::Unknown,
+            Ty::List(element) => Ty::List(Box::new(self.finish(&element))),
+            Ty::Tuple(elements) => Ty::Tuple(elements.iter().map(|ty| self.finish(ty)).collect()),
+            Ty::Function(parameters, r#return) => Ty::Function(
+                parameters.iter().map(|ty| self.finish(ty)).collect(),
+                Box::new(self.finish(&r#return)),
+            ),
+            ty => ty,
+        }
+    }
+
+    fn occurs(&self, variable: u32, ty: &Ty) -> bool {
+        match self.shallow(ty) {
+            Ty::Infer(other) => other == variable,
+            Ty::List(element) => self.occurs(variable, &element),
+            Ty::Tuple(elements) => elements.iter().any(|ty| self.occurs(variable, ty)),
+            Ty::Function(parameters, r#return) => {
+                parameters.iter().any(|ty| self.occurs(variable, ty))
+                    || self.occurs(variable, &r#return)
+            }
+            _ => false,
+        }
+    }
+
+    /// Make the two types the same, by inferring the variables in them.
+    /// Returns whether they could be.
+    fn unify(&mut self, a: &Ty, b: &Ty) -> bool {
+        match (self.shallow(a), self.shallow(b)) {
+            (Ty::Unknown, _) | (_, Ty::Unknown) => true,
+            (Ty::Infer(a), Ty::Infer(b)) if a == b => true,
+            (Ty::Infer(variable), ty) | (ty, Ty::Infer(variable)) => {
+                if self.occurs(variable, &ty) {
+                    false
+                } else {
+                    self.variables[variable as usize] = Some(ty);
+                    true
+                }
+            }
+            (Ty::List(a), Ty::List(b)) => self.unify(&a, &b),
+            (Ty::Tuple(a), Ty::Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(&b).all(|(a, b)| self.unify(a, b))
+            }
+            (Ty::Function(a, a_return), Ty::Function(b, b_return)) => {
+                a.len() == b.len()
+                    && a.iter().zip(&b).all(|(a, b)| self.unify(a, b))
+                    && self.unify(&a_return, &b_return)
+            }
+            (Ty::Named(a, _), Ty::Named(b, _)) | (Ty::Generic(a, _), Ty::Generic(b, _)) => a == b,
+            (a, b) => a == b,
+        }
+    }
+
+    /// Report an error at the span unless the type found is the one expected.
+    fn expect(&mut self, expected: &Ty, found: &Ty, span: Span) {
+        if !self.unify(expected, found) {
+            let message = format!(
+                "mismatched types: expected `{}`, found `{}`",
+                self.resolve(expected),
+                self.resolve(found),
+            );
+            self.error(span, message);
+        }
+    }
+
+    /// The return type of the function declaration, as far as it is known.
+    ///
+    /// A return type that is not declared is inferred from the body,
+    /// so the body is checked first if it has not been yet.
+    fn return_type(&mut self, function: &'h Function) -> Ty {
+        if function.return_type.is_none() {
+            self.function(function);
+        }
+        self.resolve(&self.functions[&function.id].r#return)
+    }
+
+    /// Replace the generic type parameters of the function with new inference variables.
+    fn instantiate(&mut self, function: &Function) -> impl Fn(&Ty) -> Ty {
+        let generics: HashMap<HirId, Ty> = function
+            .generics
+            .iter()
+            .map(|generic| (generic.id, self.fresh()))
+            .collect();
+        fn substitute(ty: &Ty, generics: &HashMap<HirId, Ty>) -> Ty {
+            match ty {
+                Ty::Generic(id, _) if generics.contains_key(id) => generics[id].clone(),
+                Ty::List(element) => Ty::List(Box::new(substitute(element, generics))),
+                Ty::Tuple(elements) => {
+                    Ty::Tuple(elements.iter().map(|ty| substitute(ty, generics)).collect())
+                }
+                Ty::Function(parameters, r#return) => Ty::Function(
+                    parameters
+                        .iter()
+                        .map(|ty| substitute(ty, generics))
+                        .collect(),
+                    Box::new(substitute(r#return, generics)),
+                ),
+                ty => ty.clone(),
+            }
+        }
+        move |ty| substitute(ty, &generics)
+    }
+
+    // Items
+
+    fn module(&mut self, module: &'h Module) {
+        for item in &module.items {
+            self.item(item);
+        }
+    }
+
+    fn item(&mut self, item: &'h Item) {
+        match item {
+            Item::Function(function) => self.function(function),
+            Item::Module(module) => self.module(module),
+            Item::Type(_) | Item::Enum(_) | Item::Import(_) => {}
+        }
+    }
+
+    fn function(&mut self, function: &'h Function) {
+        if !self.checked.insert(function.id) {
+            return;
+        }
+        let info = &self.functions[&function.id];
+        let parameter_types = info.signature.parameter_types.clone();
+        let r#return = info.r#return.clone();
+        for (parameter, ty) in function.parameters.iter().zip(&parameter_types) {
+            if let Some(default) = &parameter.default {
+                self.check(default, ty);
+            }
+            self.bind(&parameter.pattern, ty);
+        }
+        self.targets.insert(function.id, r#return.clone());
+        self.block(&function.body, &r#return);
+    }
+
+    // Expressions
+
+    /// Check the statements of the block, and that its value has the expected type.
+    fn block(&mut self, block: &'h Block, expected: &Ty) {
+        let mut diverges = false;
+        for statement in &block.statements {
+            match statement {
+                Stmt::Item(item) => self.item(item),
+                Stmt::Expr(expr) => {
+                    self.infer(expr);
+                    if let ExprKind::Return { .. }
+                    | ExprKind::Break { .. }
+                    | ExprKind::Continue { .. } = expr.kind
+                    {
+                        diverges = true;
+                    }
+                }
+            }
+        }
+        match &block.tail {
+            Some(tail) => self.check(tail, expected),
+            None if diverges => {}
+            None => {
+                if !self.unify(expected, &Ty::Unit) {
+                    let message = format!(
+                        "mismatched types: expected `{}`, but this block has no final expression",
+                        self.resolve(expected),
+                    );
+                    self.error(block.span, message);
+                }
+            }
+        }
+    }
+
+    /// Check that the expression has the expected type.
+    ///
+    /// Unlike inferring its type and comparing that, this lets the expected type reach
+    /// into branches and closures, so errors are reported where they are.
+    fn check(&mut self, expr: &'h Expr, expected: &Ty) {
+        match &expr.kind {
+            ExprKind::Block(block) => self.block(block, expected),
+            ExprKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.check(condition, &Ty::Bool);
+                match else_branch {
+                    Some(else_branch) => {
+                        self.block(then_branch, expected);
+                        self.block(else_branch, expected);
+                    }
+                    None => {
+                        self.block(then_branch, &Ty::Unit);
+                        self.expect(expected, &Ty::Unit, expr.span);
+                    }
+                }
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                let ty = self.infer(scrutinee);
+                self.matches.push((scrutinee, &arms[..], ty.clone()));
+                for arm in arms {
+                    self.bind(&arm.pattern, &ty);
+                    if let Some(guard) = &arm.guard {
+                        self.check(guard, &Ty::Bool);
+                    }
+                    self.check(&arm.body, expected);
+                }
+            }
+            ExprKind::Closure(closure) => {
+                let ty = self.closure(expr, closure, expected);
+                self.expect(expected, &ty, expr.span);
+                self.typing.exprs.insert(expr.id, ty);
+                return;
+            }
+            _ => {
+                let ty = self.infer(expr);
+                self.expect(expected, &ty, expr.span);
+                return;
+            }
+        }
+        self.typing.exprs.insert(expr.id, expected.clone());
+    }
+
+    /// Infer the type of the expression.
+    fn infer(&mut self, expr: &'h Expr) -> Ty {
+        let ty = match &expr.kind {
+            ExprKind::Block(_) | ExprKind::If { .. } | ExprKind::Match { .. } => {
+                let ty = self.fresh();
+                self.check(expr, &ty);
+                return ty;
+            }
+            ExprKind::Literal(literal) => literal_type(literal),
+            ExprKind::Path(path) => self.path(path),
+            ExprKind::Tuple(elements) => {
+                Ty::Tuple(elements.iter().map(|element| self.infer(element)).collect())
+            }
+            ExprKind::List(elements) => {
+                let element = self.fresh();
+                for expr in elements {
+                    self.check(expr, &element);
+                }
+                Ty::List(Box::new(element))
+            }
+            ExprKind::Record { path, fields } => {
+                let record = match *self.res(path) {
+                    Res::Type(id) => self.records.get(&id),
+                    Res::Variant(id) => self.variants.get(&id),
+                    _ => None,
+                };
+                let (ty, name, declared) = match record {
+                    Some(record) => (record.ty.clone(), record.name.clone(), &record.fields),
+                    None => {
+                        if *self.res(path) != Res::Err {
+                            let message = format!("expected a record type, found `{}`", path);
+                            self.error(path.span, message);
+                        }
+                        for field in fields {
+                            self.infer(&field.value);
+                        }
+                        return self.record(expr, Ty::Unknown);
+                    }
+                };
+                let mut given: Vec<&str> = vec![];
+                let mut checks = vec![];
+                for field in fields {
+                    let text = field.name.text.as_str();
+                    match declared.iter().find(|(name, _)| name == text) {
+                        _ if given.contains(&text) => {
+                            let message = format!("field `{}` is given more than once", text);
+                            self.diagnostics
+                                .push(Diagnostic::error(field.name.span, message));
+                            checks.push((&field.value, Ty::Unknown));
+                        }
+                        Some((_, ty)) => {
+                            given.push(text);
+                            checks.push((&field.value, ty.clone()));
+                        }
+                        None => {
+                            let message = format!("`{}` has no field `{}`", name, text);
+                            self.diagnostics
+                                .push(Diagnostic::error(field.name.span, message));
+                            checks.push((&field.value, Ty::Unknown));
+                        }
+                    }
+                }
+                let missing: Vec<_> = declared
+                    .iter()
+                    .filter(|(name, _)| !given.contains(&name.as_str()))
+                    .map(|(name, _)| format!("`{}`", name))
+                    .collect();
+                if !missing.is_empty() {
+                    let message = format!(
+                        "missing field{} {} in `{}`",
+                        if missing.len() == 1 { "" } else { "s" },
+                        missing.join(", "),
+                        name,
+                    );
+                    self.error(expr.span, message);
+                }
+                for (value, ty) in checks {
+                    self.check(value, &ty);
+                }
+                ty
+            }
+            ExprKind::Closure(closure) => self.closure(expr, closure, &Ty::Unknown),
+            ExprKind::Call {
+                callee,
+                arguments,
+                trailing_closure,
+            } => {
+                let res = match &callee.kind {
+                    ExprKind::Path(path) => Some((path, self.res(path))),
+                    _ => None,
+                };
+                let target = match res {
+                    Some((path, Res::Functions(overloads))) => {
+                        Callee::Overloads(overloads.clone(), path.to_string())
+                    }
+                    Some((_, &Res::Variant(id)))
+                        if self.variants.get(&id).is_some_and(|v| !v.fields.is_empty()) =>
+                    {
+                        Callee::Variant(id)
+                    }
+                    _ => Callee::Value(self.infer(callee)),
+                };
+                let ty = self.call(expr, target, None, arguments, trailing_closure.as_deref());
+                if let (Some(selection), ExprKind::Path(_)) =
+                    (self.typing.overloads.get(expr), &callee.kind)
+                {
+                    let info = &self.functions[&selection.function];
+                    let ty = Ty::Function(
+                        info.signature.parameter_types.clone(),
+                        Box::new(info.r#return.clone()),
+                    );
+                    self.typing.exprs.insert(callee.id, ty);
+                }
+                ty
+            }
+            ExprKind::MethodCall {
+                receiver,
+                method,
+                arguments,
+                trailing_closure,
+            } => {
+                let target = match self.resolutions.method(expr).unwrap_or(&Res::Err) {
+                    Res::Functions(overloads) => {
+                        Callee::Overloads(overloads.clone(), method.text.clone())
+                    }
+                    Res::Local(id) => {
+                        Callee::Value(self.typing.bindings.get(id).cloned().unwrap_or(Ty::Unknown))
+                    }
+                    _ => Callee::Value(Ty::Unknown),
+                };
+                self.call(
+                    expr,
+                    target,
+                    Some(receiver),
+                    arguments,
+                    trailing_closure.as_deref(),
+                )
+            }
+            ExprKind::Field { receiver, field } => {
+                let receiver_type = self.infer(receiver);
+                match self.shallow(&receiver_type) {
+                    Ty::Unknown => Ty::Unknown,
+                    Ty::Infer(_) => {
+                        let message = format!(
+                            "the type of this value must be known to access its field `{}`",
+                            field.text,
+                        );
+                        self.error(receiver.span, message);
+                        Ty::Unknown
+                    }
+                    ty => match &ty {
+                        Ty::Named(id, _) if self.records.contains_key(id) => {
+                            match self.records[id].get(&field.text) {
+                                Some(ty) => ty.clone(),
+                                None => {
+                                    let message = format!("`{}` has no field `{}`", ty, field.text);
+                                    self.error(field.span, message);
+                                    Ty::Unknown
+                                }
+                            }
+                        }
+                        _ => {
+                            let message =
+                                format!("`{}` has no field `{}`", self.resolve(&ty), field.text);
+                            self.error(field.span, message);
+                            Ty::Unknown
+                        }
+                    },
+                }
+            }
+            ExprKind::Index { receiver, index } => {
+                let element = self.fresh();
+                let list = Ty::List(Box::new(element.clone()));
+                self.check(receiver, &list);
+                self.check(index, &Ty::Int);
+                element
+            }
+            ExprKind::Loop { body, .. } => {
+                let ty = self.fresh();
+                self.targets.insert(expr.id, ty.clone());
+                let body_type = self.fresh();
+                self.block(body, &body_type);
+                ty
+            }
+            ExprKind::For {
+                pattern,
+                iterable,
+                body,
+                ..
+            } => {
+                let element = self.fresh();
+                self.check(iterable, &Ty::List(Box::new(element.clone())));
+                self.bind(pattern, &element);
+                self.targets.insert(expr.id, Ty::Unit);
+                let body_type = self.fresh();
+                self.block(body, &body_type);
+                Ty::Unit
+            }
+            ExprKind::Break { target, value } | ExprKind::Return { target, value } => {
+                let expected = self.targets.get(target).cloned().unwrap_or(Ty::Unknown);
+                match value {
+                    Some(value) => self.check(value, &expected),
+                    None => self.expect(&expected, &Ty::Unit, expr.span),
+                }
+                self.fresh()
+            }
+            ExprKind::Continue { .. } => self.fresh(),
+        };
+        self.record(expr, ty)
+    }
+
+    fn record(&mut self, expr: &Expr, ty: Ty) -> Ty {
+        self.typing.exprs.insert(expr.id, ty.clone());
+        ty
+    }
+
+    /// The type of the value a path used as an expression refers to.
+    fn path(&mut self, path: &Path) -> Ty {
+        let kind = match self.res(path) {
+            Res::Local(id) => return self.typing.bindings.get(id).cloned().unwrap_or(Ty::Unknown),
+            Res::Functions(overloads) if overloads.len() == 1 => {
+                let function = self.functions[&overloads[0]].signature.function;
+                let r#return = self.return_type(function);
+                let instantiate = self.instantiate(function);
+                let info = &self.functions[&function.id];
+                return Ty::Function(
+                    info.signature
+                        .parameter_types
+                        .iter()
+                        .map(&instantiate)
+                        .collect(),
+                    Box::new(instantiate(&r#return)),
+                );
+            }
+            Res::Functions(_) => {
+                let message = format!(
+                    "`{}` is overloaded, so it must be called to select one of its overloads",
+                    path,
+                );
+                self.error(path.span, message);
+                return Ty::Unknown;
+            }
+            Res::Variant(id) => {
+                let variant = &self.variants[id];
+                return if variant.fields.is_empty() {
+                    variant.ty.clone()
+                } else {
+                    Ty::Function(
+                        variant.fields.iter().map(|(_, ty)| ty.clone()).collect(),
+                        Box::new(variant.ty.clone()),
+                    )
+                };
+            }
+            Res::Err => return Ty::Unknown,
+            Res::Type(_) => "type",
+            Res::Enum(_) => "enum",
+            Res::Module(_) => "module",
+            Res::Generic(_) => "type parameter",
+            Res::Builtin(_) => "builtin type",
+        };
+        let message = format!("expected a value, found {} `{}`", kind, path);
+        self.error(path.span, message);
+        Ty::Unknown
+    }
+
+    /// The type of a closure, with the types of its parameters taken from
+    /// the function type expected of it where they are not written.
+    fn closure(&mut self, expr: &'h Expr, closure: &'h Closure, expected: &Ty) -> Ty {
+        let (expected_parameters, expected_return) = match self.shallow(expected) {
+            Ty::Function(parameters, r#return) if parameters.len() == closure.parameters.len() => {
+                (Some(parameters), Some(*r#return))
+            }
+            _ => (None, None),
+        };
+        let mut parameter_types = vec![];
+        for (index, parameter) in closure.parameters.iter().enumerate() {
+            let ty = match (&parameter.ty, &expected_parameters) {
+                (Some(path), _) => self.ty(path),
+                (None, Some(expected)) => expected[index].clone(),
+                (None, None) => self.fresh(),
+            };
+            self.bind(&parameter.pattern, &ty);
+            parameter_types.push(ty);
+        }
+        let r#return = match expected_return {
+            Some(r#return) => r#return,
+            None => self.fresh(),
+        };
+        self.targets.insert(expr.id, r#return.clone());
+        self.block(&closure.body, &r#return);
+        Ty::Function(parameter_types, Box::new(r#return))
+    }
+
+    /// Check a call, and return the type of its value.
+    ///
+    /// The receiver of a method call is passed as its first argument.
+    fn call(
+        &mut self,
+        expr: &'h Expr,
+        callee: Callee,
+        receiver: Option<&'h Expr>,
+        arguments: &'h [Argument],
+        trailing_closure: Option<&'h Expr>,
+    ) -> Ty {
+        match callee {
+            Callee::Overloads(overloads, name) => self.overloaded_call(
+                expr,
+                &overloads,
+                &name,
+                receiver,
+                arguments,
+                trailing_closure,
+            ),
+            Callee::Variant(id) => {
+                let values = self.positional(receiver, arguments, trailing_closure);
+                let variant = &self.variants[&id];
+                let ty = variant.ty.clone();
+                let fields: Vec<_> = variant.fields.iter().map(|(_, ty)| ty.clone()).collect();
+                if values.len() != fields.len() {
+                    let message = format!(
+                        "`{}` has {} field{}, but {} were given",
+                        variant.name,
+                        fields.len(),
+                        if fields.len() == 1 { "" } else { "s" },
+                        values.len(),
+                    );
+                    self.error(expr.span, message);
+                }
+                for (index, value) in values.into_iter().enumerate() {
+                    match fields.get(index) {
+                        Some(field) => self.check(value, field),
+                        None => drop(self.infer(value)),
+                    }
+                }
+                ty
+            }
+            Callee::Value(callee_type) => {
+                let values = self.positional(receiver, arguments, trailing_closure);
+                let (parameters, r#return) = match self.shallow(&callee_type) {
+                    Ty::Function(parameters, r#return) => (parameters, *r#return),
+                    Ty::Infer(_) => {
+                        let parameters: Vec<_> = values.iter().map(|_| self.fresh()).collect();
+                        let r#return = self.fresh();
+                        let function = Ty::Function(parameters.clone(), Box::new(r#return.clone()));
+                        self.unify(&callee_type, &function);
+                        (parameters, r#return)
+                    }
+                    Ty::Unknown => (vec![Ty::Unknown; values.len()], Ty::Unknown),
+                    ty => {
+                        let message = format!("expected a function, found `{}`", self.resolve(&ty));
+                        self.error(expr.span, message);
+                        (vec![Ty::Unknown; values.len()], Ty::Unknown)
+                    }
+                };
+                if parameters.len() != values.len() {
+                    let message = format!(
+                        "this function takes {} argument{}, but {} were given",
+                        parameters.len(),
+                        if parameters.len() == 1 { "" } else { "s" },
+                        values.len(),
+                    );
+                    self.error(expr.span, message);
+                }
+                for (index, value) in values.into_iter().enumerate() {
+                    match parameters.get(index) {
+                        Some(parameter) => self.check(value, parameter),
+                        None => drop(self.infer(value)),
+                    }
+                }
+                r#return
+            }
+        }
+    }
+
+    /// The arguments of a call of something other than a function declaration,
+    /// which binds them by position alone.
+    fn positional(
+        &mut self,
+        receiver: Option<&'h Expr>,
+        arguments: &'h [Argument],
+        trailing_closure: Option<&'h Expr>,
+    ) -> Vec<&'h Expr> {
+        for argument in arguments {
+            if let Some(name) = &argument.name {
+                let message = "only calls of function declarations can name their arguments";
+                self.error(name.span, message);
+            }
+        }
+        receiver
+            .into_iter()
+            .chain(arguments.iter().map(|argument| &argument.value))
+            .chain(trailing_closure)
+            .collect()
+    }
+
+    fn overloaded_call(
+        &mut self,
+        expr: &'h Expr,
+        overloads: &[HirId],
+        name: &str,
+        receiver: Option<&'h Expr>,
+        arguments: &'h [Argument],
+        trailing_closure: Option<&'h Expr>,
+    ) -> Ty {
+        // Closures are checked once the overload is selected, to know their parameter types.
+        let mut unchecked = vec![];
+        let mut argument_type = |checker: &mut Self, value: &'h Expr| match &value.kind {
+            ExprKind::Closure(closure) => {
+                unchecked.push(value);
+                Ty::Function(
+                    closure
+                        .parameters
+                        .iter()
+                        .map(|parameter| match &parameter.ty {
+                            Some(ty) => {
+                                Ty::from_path(ty, checker.resolutions).unwrap_or(Ty::Unknown)
+                            }
+                            None => Ty::Unknown,
+                        })
+                        .collect(),
+                    Box::new(Ty::Unknown),
+                )
+            }
+            _ => {
+                let ty = checker.infer(value);
+                checker.resolve(&ty)
+            }
+        };
+        let mut values = vec![];
+        let mut call_arguments = vec![];
+        if let Some(receiver) = receiver {
+            let ty = argument_type(self, receiver);
+            values.push(receiver);
+            call_arguments.push(CallArgument::receiver(receiver, ty));
+        }
+        for argument in arguments {
+            let ty = argument_type(self, &argument.value);
+            values.push(&argument.value);
+            call_arguments.push(CallArgument::new(argument, ty));
+        }
+        let closure_type =
+            trailing_closure.map(|closure| (closure.span, argument_type(self, closure)));
+
+        let candidates: Vec<_> = overloads
+            .iter()
+            .map(|id| self.functions[id].signature.clone())
+            .collect();
+        let call = CallSite {
+            span: expr.span,
+            name,
+            arguments: &call_arguments,
+            trailing_closure: closure_type.as_ref().map(|(span, ty)| (*span, ty)),
+        };
+        let labels: Vec<_> = arguments
+            .iter()
+            .filter_map(|argument| argument.name.as_ref())
+            .map(|name| &name.text)
+            .collect();
+        let repeated_label =
+            (0..labels.len()).any(|index| labels[..index].contains(&labels[index]));
+        let selection = match overload::select(&candidates, &call) {
+            Ok(selection) => selection,
+            Err(diagnostic) => {
+                // Lowering reports labels given twice, whichever overload is meant.
+                if !repeated_label {
+                    self.diagnostics.push(diagnostic);
+                }
+                for closure in unchecked {
+                    self.infer(closure);
+                }
+                return Ty::Unknown;
+            }
+        };
+
+        let function = self.functions[&selection.function].signature.function;
+        let r#return = self.return_type(function);
+        let instantiate = self.instantiate(function);
+        let info = &self.functions[&selection.function];
+        let parameters: Vec<_> = info
+            .signature
+            .parameter_types
+            .iter()
+            .map(&instantiate)
+            .collect();
+        let r#return = instantiate(&r#return);
+        let argument = |checker: &mut Self, value: &'h Expr, ty: &Ty, expected: &Ty| {
+            if let ExprKind::Closure(_) = value.kind {
+                checker.check(value, expected);
+            } else {
+                checker.expect(expected, ty, value.span);
+            }
+        };
+        for (parameter, binding) in parameters.iter().zip(&selection.bindings) {
+            match binding {
+                ParameterBinding::Argument(index) => {
+                    argument(self, values[*index], &call_arguments[*index].ty, parameter)
+                }
+                ParameterBinding::Variadic(indices) => {
+                    let element = match self.shallow(parameter) {
+                        Ty::List(element) => *element,
+                        _ => Ty::Unknown,
+                    };
+                    for &index in indices {
+                        argument(self, values[index], &call_arguments[index].ty, &element);
+                    }
+                }
+                ParameterBinding::TrailingClosure => {
+                    let closure = trailing_closure.unwrap();
+                    argument(self, closure, &closure_type.as_ref().unwrap().1, parameter);
+                }
+                ParameterBinding::Default => {}
+            }
+        }
+        self.typing.overloads.insert(expr, selection);
+        r#return
+    }
+
+    // Patterns
+
+    /// Check that the pattern matches values of the type, and bind its names.
+    fn bind(&mut self, pattern: &'h Pattern, ty: &Ty) {
+        match &pattern.kind {
+            PatternKind::Wildcard => {}
+            PatternKind::Binding(_) => {
+                self.typing.bindings.insert(pattern.id, ty.clone());
+            }
+            PatternKind::Literal(literal) => self.expect(ty, &literal_type(literal), pattern.span),
+            PatternKind::Tuple(elements) => {
+                let types: Vec<_> = elements.iter().map(|_| self.fresh()).collect();
+                self.expect(ty, &Ty::Tuple(types.clone()), pattern.span);
+                for (element, ty) in elements.iter().zip(&types) {
+                    self.bind(element, ty);
+                }
+            }
+            PatternKind::Constructor { path, fields } => {
+                let variant = match *self.res(path) {
+                    Res::Variant(id) => &self.variants[&id],
+                    ref res => {
+                        if *res != Res::Err {
+                            let message = format!("expected an enum variant, found `{}`", path);
+                            self.error(path.span, message);
+                        }
+                        for field in fields {
+                            self.bind(field, &Ty::Unknown);
+                        }
+                        return;
+                    }
+                };
+                let variant_type = variant.ty.clone();
+                let types: Vec<_> = variant.fields.iter().map(|(_, ty)| ty.clone()).collect();
+                if types.len() != fields.len() {
+                    let message = format!(
+                        "`{}` has {} field{}, but the pattern has {}",
+                        variant.name,
+                        types.len(),
+                        if types.len() == 1 { "" } else { "s" },
+                        fields.len(),
+                    );
+                    self.error(pattern.span, message);
+                }
+                self.expect(ty, &variant_type, pattern.span);
+                for (index, field) in fields.iter().enumerate() {
+                    let ty = types.get(index).cloned().unwrap_or(Ty::Unknown);
+                    self.bind(field, &ty);
+                }
+            }
+            PatternKind::Record { path, fields } => {
+                let record = match *self.res(path) {
+                    Res::Type(id) => self.records.get(&id),
+                    Res::Variant(id) => self.variants.get(&id),
+                    _ => None,
+                };
+                let record = match record {
+                    Some(record) => record,
+                    None => {
+                        if *self.res(path) != Res::Err {
+                            let message = format!("expected a record type, found `{}`", path);
+                            self.error(path.span, message);
+                        }
+                        for field in fields {
+                            self.bind(&field.pattern, &Ty::Unknown);
+                        }
+                        return;
+                    }
+                };
+                let record_type = record.ty.clone();
+                let types: Vec<_> = fields
+                    .iter()
+                    .map(|field| (record.get(&field.name.text).cloned(), record.name.clone()))
+                    .collect();
+                self.expect(ty, &record_type, pattern.span);
+                for (field, (ty, name)) in fields.iter().zip(types) {
+                    let ty = ty.unwrap_or_else(|| {
+                        let message = format!("`{}` has no field `{}`", name, field.name.text);
+                        self.error(field.name.span, message);
+                        Ty::Unknown
+                    });
+                    self.bind(&field.pattern, &ty);
+                }
+            }
+        }
+    }
+
+    // Exhaustiveness
+
+    /// Check that the arms of a `match` on a value of the type cover every value,
+    /// and report those that cannot be reached.
+    fn arms(&mut self, scrutinee: &Expr, arms: &[Arm], ty: &Ty) {
+        let types = [ty.clone()];
+        let mut rows: Vec<Vec<Pat>> = vec![];
+        for arm in arms {
+            let row = vec![self.deconstruct(&arm.pattern)];
+            if self.useful(&rows, &row, &types).is_none() {
+                self.diagnostics.push(Diagnostic::warning(
+                    arm.pattern.span,
+                    "unreachable arm: every value it matches is matched by an earlier arm",
+                ));
+            }
+            if arm.guard.is_none() {
+                rows.push(row);
+            }
+        }
+        // Without the type, which was reported as an error, the values cannot be known.
+        if ty.contains_unknown() {
+            return;
+        }
+        if let Some(witness) = self.useful(&rows, &[Pat::Wild], &types) {
+            let message = format!(
+                "this `match` does not cover every value: `{}` is not matched",
+                self.describe(&witness[0]),
+            );
+            let mut diagnostic = Diagnostic::error(scrutinee.span, message);
+            if arms.iter().any(|arm| arm.guard.is_some()) {
+                diagnostic = diagnostic
+                    .with_note("arms with a guard do not count, as the guard may not hold");
+            }
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// The pattern as a tree of the constructors it matches.
+    fn deconstruct(&self, pattern: &Pattern) -> Pat {
+        let (constructor, fields) = match &pattern.kind {
+            PatternKind::Wildcard | PatternKind::Binding(_) => return Pat::Wild,
+            PatternKind::Literal(literal) => (
+                match literal {
+                    Literal::Integer(digits) => {
+                        let digits = digits.trim_start_matches('0');
+                        Constructor::Int(if digits.is_empty() { "0" } else { digits }.to_string())
+                    }
+                    Literal::Boolean(value) => Constructor::Bool(*value),
+                    Literal::Character(value) => Constructor::Char(*value),
+                    Literal::Unit => Constructor::Unit,
+                },
+                vec![],
+            ),
+            PatternKind::Tuple(elements) => (
+                Constructor::Tuple(elements.len()),
+                elements
+                    .iter()
+                    .map(|element| self.deconstruct(element))
+                    .collect(),
+            ),
+            PatternKind::Constructor { path, fields } => match *self.res(path) {
+                Res::Variant(id) => {
+                    let arity = self.variants[&id].fields.len();
+                    let mut fields: Vec<_> =
+                        fields.iter().map(|field| self.deconstruct(field)).collect();
+                    fields.resize(arity, Pat::Wild);
+                    (Constructor::Variant(id), fields)
+                }
+                _ => return Pat::Wild,
+            },
+            PatternKind::Record { path, fields } => {
+                let (constructor, record) = match *self.res(path) {
+                    Res::Type(id) if self.records.contains_key(&id) => {
+                        (Constructor::Record(id), &self.records[&id])
+                    }
+                    Res::Variant(id) => (Constructor::Variant(id), &self.variants[&id]),
+                    _ => return Pat::Wild,
+                };
+                let fields = record
+                    .fields
+                    .iter()
+                    .map(
+                        |(name, _)| match fields.iter().find(|field| field.name.text == *name) {
+                            Some(field) => self.deconstruct(&field.pattern),
+                            None => Pat::Wild,
+                        },
+                    )
+                    .collect();
+                (constructor, fields)
+            }
+        };
+        Pat::Constructor(constructor, fields)
+    }
+
+    /// Every constructor of values of the type, unless there are too many to list.
+    fn constructors(&self, ty: &Ty) -> Option<Vec<Constructor>> {
+        match ty {
+            Ty::Bool => Some(vec![Constructor::Bool(false), Constructor::Bool(true)]),
+            Ty::Unit => Some(vec![Constructor::Unit]),
+            Ty::Tuple(elements) => Some(vec![Constructor::Tuple(elements.len())]),
+            Ty::Named(id, _) if self.records.contains_key(id) => {
+                Some(vec![Constructor::Record(*id)])
+            }
+            Ty::Named(id, _) => self.enums.get(id).map(|variants| {
+                variants
+                    .iter()
+                    .map(|&variant| Constructor::Variant(variant))
+                    .collect()
+            }),
+            _ => None,
+        }
+    }
+
+    /// The types of the fields of a constructor of values of the type.
+    fn field_types(&self, constructor: &Constructor, ty: &Ty) -> Vec<Ty> {
+        let fields = match constructor {
+            Constructor::Tuple(arity) => {
+                return match ty {
+                    Ty::Tuple(elements) if elements.len() == *arity => elements.clone(),
+                    _ => vec![Ty::Unknown; *arity],
+                };
+            }
+            Constructor::Variant(id) => &self.variants[id],
+            Constructor::Record(id) => &self.records[id],
+            _ => return vec![],
+        };
+        fields.fields.iter().map(|(_, ty)| ty.clone()).collect()
+    }
+
+    /// Whether some values that `row` matches are matched by none of `rows`,
+    /// the patterns of each matching values of `types` in turn.
+    /// If so, returns one of them, as patterns.
+    ///
+    /// This is the usefulness algorithm of Maranget's
+    /// [Warnings for pattern matching](http://moscova.inria.fr/~maranget/papers/warn/index.html).
+    fn useful(&self, rows: &[Vec<Pat>], row: &[Pat], types: &[Ty]) -> Option<Vec<Pat>> {
+        let (head, rest) = match row.split_first() {
+            Some(split) => split,
+            None if rows.is_empty() => return Some(vec![]),
+            None => return None,
+        };
+        if let Pat::Constructor(constructor, fields) = head {
+            return self.useful_constructor(rows, constructor, fields, rest, types);
+        }
+        let mut used: Vec<&Constructor> = vec![];
+        for row in rows {
+            if let Pat::Constructor(constructor, _) = &row[0] {
+                if !used.contains(&constructor) {
+                    used.push(constructor);
+                }
+            }
+        }
+        let all = self.constructors(&types[0]);
+        if let Some(all) = &all {
+            if all.iter().all(|constructor| used.contains(&constructor)) {
+                // The wildcard matches nothing more than the constructors it could be.
+                return all.iter().find_map(|constructor| {
+                    let fields = vec![Pat::Wild; self.field_types(constructor, &types[0]).len()];
+                    self.useful_constructor(rows, constructor, &fields, rest, types)
+                });
+            }
+        }
+        // A constructor no row starts with is only matched by the rows starting with a wildcard.
+        let rows: Vec<_> = rows
+            .iter()
+            .filter(|row| row[0] == Pat::Wild)
+            .map(|row| row[1..].to_vec())
+            .collect();
+        let mut witness = self.useful(&rows, rest, &types[1..])?;
+        let missing = all
+            .into_iter()
+            .flatten()
+            .find(|constructor| !used.contains(&constructor));
+        let head = match missing {
+            Some(constructor) if !used.is_empty() => {
+                let fields = vec![Pat::Wild; self.field_types(&constructor, &types[0]).len()];
+                Pat::Constructor(constructor, fields)
+            }
+            _ => Pat::Wild,
+        };
+        witness.insert(0, head);
+        Some(witness)
+    }
+
+    /// [`useful`](Checker::useful), for a row starting with a constructor.
+    fn useful_constructor(
+        &self,
+        rows: &[Vec<Pat>],
+        constructor: &Constructor,
+        fields: &[Pat],
+        rest: &[Pat],
+        types: &[Ty],
+    ) -> Option<Vec<Pat>> {
+        let field_types = self.field_types(constructor, &types[0]);
+        let arity = field_types.len();
+        let rows: Vec<_> = rows
+            .iter()
+            .filter_map(|row| {
+                let mut specialized = match &row[0] {
+                    Pat::Wild => vec![Pat::Wild; arity],
+                    Pat::Constructor(other, fields) if other == constructor => fields.clone(),
+                    Pat::Constructor(..) => return None,
+                };
+                specialized.extend_from_slice(&row[1..]);
+                Some(specialized)
+            })
+            .collect();
+        let row: Vec<_> = fields.iter().chain(rest).cloned().collect();
+        let types: Vec<_> = field_types
+            .into_iter()
+            .chain(types[1..].iter().cloned())
+            .collect();
+        let mut witness = self.useful(&rows, &row, &types)?;
+        let fields = witness.drain(..arity).collect();
+        witness.insert(0, Pat::Constructor(constructor.clone(), fields));
+        Some(witness)
+    }
+
+    /// The pattern as it would be written.
+    fn describe(&self, pat: &Pat) -> String {
+        let (constructor, fields) = match pat {
+            Pat::Wild => return "_".to_string(),
+            Pat::Constructor(constructor, fields) => (constructor, fields),
+        };
+        let fields: Vec<_> = fields.iter().map(|field| self.describe(field)).collect();
+        match constructor {
+            Constructor::Bool(value) => value.to_string(),
+            Constructor::Int(digits) => digits.clone(),
+            Constructor::Char(value) => format!("{:?}", value),
+            Constructor::Unit => "()".to_string(),
+            Constructor::Tuple(1) => format!("({},)", fields[0]),
+            Constructor::Tuple(_) => format!("({})", fields.join(", ")),
+            Constructor::Variant(id) if fields.is_empty() => self.variants[id].name.clone(),
+            Constructor::Variant(id) => {
+                format!("{}({})", self.variants[id].name, fields.join(", "))
+            }
+            Constructor::Record(id) => {
+                let record = &self.records[id];
+                let fields: Vec<_> = record
+                    .fields
+                    .iter()
+                    .zip(&fields)
+                    .map(|((name, _), field)| format!("{} = {}", name, field))
+                    .collect();
+                format!("{} {{ {} }}", record.name, fields.join(", "))
+            }
+        }
+    }
+}
+
+fn literal_type(literal: &Literal) -> Ty {
+    match literal {
+        Literal::Integer(_) => Ty::Int,
+        Literal::Boolean(_) => Ty::Bool,
+        Literal::Character(_) => Ty::Char,
+        Literal::Unit => Ty::Unit,
+    }
+}
+
+/// A pattern, as exhaustiveness checking sees it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Pat {
+    /// Matches any value, whether or not it binds it.
+    Wild,
+    /// Matches the values of a constructor whose fields match the patterns.
+    Constructor(Constructor, Vec<Pat>),
+}
+
+/// A way of constructing a value, which patterns tell apart.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Constructor {
+    Bool(bool),
+    /// An integer, by its digits without leading zeros.
+    Int(String),
+    Char(char),
+    Unit,
+    /// A tuple, by its number of elements.
+    Tuple(usize),
+    /// An enum variant, by the id of its declaration.
+    Variant(HirId),
+    /// A record type, by the id of its declaration.
+    Record(HirId),
+}
@@ -0,0 +1,212 @@
+//! Desugaring of calls, which makes every argument an ordinary positional argument.
+//!
+//! Once types are checked, and so the overload of every call is selected,
+//! each call is rewritten so that all of its arguments are within the parentheses:
+//!
+//! - The receiver of a method call `x.f(y)` becomes the first argument of the call `f(x, y)`.
+//! - A trailing closure becomes the argument of the parameter it binds.
+//! - Labelled arguments lose their label, and move to the position of the parameter they bind.
+//! - The arguments collected by a variadic parameter become a single list argument.
+//!
+//! A call of a function declaration then has one argument for each of its parameters that is
+//! not left to its default value, in the order the parameters are declared, and its
+//! [`Selection`] binds them in that order. Arguments are evaluated in that order, too,
+//! rather than in the order they are written.
+//!
+//! A call whose overload could not be selected keeps the labels of its arguments,
+//! as there is no parameter order to put them in.
+//!
+//! This is a pass after type checking, rather than part of lowering, because the parameter
+//! a labelled argument or trailing closure binds depends on which overload the call selects,
+//! and so on the types of its arguments. Lowering only reports a label given twice in one call,
+//! and [overload selection](crate::overload) reports labels no overload declares.
+
+use crate::{
+    check::Typing,
+    expressions::{Argument, Expr, ExprKind, Literal},
+    items::Program,
+    overload::{ParameterBinding, Selection},
+    paths::{Name, Path, PathSegment},
+    resolve::{Res, Resolutions},
+    types::Ty,
+    visit::{self, VisitorMut},
+    HirId,
+};
+use std::mem;
+
+/// Rewrite every call of the program into one with positional arguments alone.
+/// See the [module documentation](self).
+///
+/// The resolutions and typing of the program are updated to match.
+pub fn desugar_calls(program: &mut Program, resolutions: &mut Resolutions, typing: &mut Typing) {
+    let mut desugarer = Desugarer {
+        resolutions,
+        typing,
+        next_id: program.next_id,
+    };
+    visit::walk_program_mut(&mut desugarer, program);
+    program.next_id = desugarer.next_id;
+}
+
+struct Desugarer<'a> {
+    resolutions: &'a mut Resolutions,
+    typing: &'a mut Typing,
+    next_id: u32,
+}
+
+impl<'a> VisitorMut for Desugarer<'a> {
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        visit::walk_expr_mut(self, expr);
+        expr.kind = match mem::replace(&mut expr.kind, ExprKind::Literal(Literal::Unit)) {
+            ExprKind::Call {
+                callee,
+                arguments,
+                trailing_closure,
+            } => self.call(expr, callee, None, arguments, trailing_closure),
+            ExprKind::MethodCall {
+                receiver,
+                method,
+                arguments,
+                trailing_closure,
+            } => {
+                let res = self.resolutions.method(expr).cloned().unwrap_or(Res::Err);
+                let callee = self.method_callee(method, res);
+                let receiver = Argument {
+                    span: receiver.span,
+                    name: None,
+                    value: *receiver,
+                };
+                self.call(
+                    expr,
+                    Box::new(callee),
+                    Some(receiver),
+                    arguments,
+                    trailing_closure,
+                )
+            }
+            kind => kind,
+        };
+    }
+}
+
+impl<'a> Desugarer<'a> {
+    fn id(&mut self) -> HirId {
+        self.next_id += 1;
+        HirId(self.next_id - 1)
+    }
+
+    /// A path expression naming the function a method call calls.
+    fn method_callee(&mut self, method: Name, res: Res) -> Expr {
+        let path = Path {
+            id: self.id(),
+            span: method.span,
+            global: false,
+            segments: vec![PathSegment {
+                span: method.span,
+                name: method,
+                arguments: vec![],
+            }],
+        };
+        self.resolutions.insert(&path, res);
+        Expr {
+            id: self.id(),
+            span: path.span,
+            kind: ExprKind::Path(path),
+        }
+    }
+
+    /// The call with its arguments in the order of the parameters they bind.
+    ///
+    /// The receiver of a method call is its first argument.
+    fn call(
+        &mut self,
+        call: &Expr,
+        callee: Box<Expr>,
+        receiver: Option<Argument>,
+        arguments: Vec<Argument>,
+        trailing_closure: Option<Box<Expr>>,
+    ) -> ExprKind {
+        let mut written: Vec<_> = receiver.into_iter().chain(arguments).map(Some).collect();
+        let mut trailing_closure = trailing_closure.map(|closure| Argument {
+            span: closure.span,
+            name: None,
+            value: *closure,
+        });
+        let selection = match self.typing.overloads.get(call) {
+            Some(selection) => selection.clone(),
+            None => {
+                let arguments = written
+                    .into_iter()
+                    .flatten()
+                    .chain(trailing_closure)
+                    .collect();
+                return ExprKind::Call {
+                    callee,
+                    arguments,
+                    trailing_closure: None,
+                };
+            }
+        };
+
+        let mut arguments = vec![];
+        let mut bindings = vec![];
+        for binding in &selection.bindings {
+            let argument = match binding {
+                ParameterBinding::Argument(index) => written[*index].take().unwrap(),
+                ParameterBinding::TrailingClosure => trailing_closure.take().unwrap(),
+                ParameterBinding::Variadic(indices) => {
+                    let elements: Vec<_> = indices
+                        .iter()
+                        .map(|&index| written[index].take().unwrap().value)
+                        .collect();
+                    self.variadic_list(call, elements)
+                }
+                ParameterBinding::Default => {
+                    bindings.push(ParameterBinding::Default);
+                    continue;
+                }
+            };
+            bindings.push(ParameterBinding::Argument(arguments.len()));
+            arguments.push(Argument {
+                name: None,
+                ..argument
+            });
+        }
+        self.typing.overloads.insert(
+            call,
+            Selection {
+                function: selection.function,
+                bindings,
+            },
+        );
+        ExprKind::Call {
+            callee,
+            arguments,
+            trailing_closure: None,
+        }
+    }
+
+    /// The list of the arguments a variadic parameter collects.
+    fn variadic_list(&mut self, call: &Expr, elements: Vec<Expr>) -> Argument {
+        let span = match (elements.first(), elements.last()) {
+            (Some(first), Some(last)) => first.span.to(last.span),
+            _ => call.span,
+        };
+        let element_type = elements
+            .first()
+            .map_or(Ty::Unknown, |element| self.typing.expr(element).clone());
+        let list = Expr {
+            id: self.id(),
+            span,
+            kind: ExprKind::List(elements),
+        };
+        self.typing
+            .exprs
+            .insert(list.id, Ty::List(Box::new(element_type)));
+        Argument {
+            span,
+            name: None,
+            value: list,
+        }
+    }
+}
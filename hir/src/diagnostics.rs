@@ -0,0 +1,128 @@
+//! Diagnostics report problems found in a program, pointing at the source responsible.
+
+use crate::{prelude, SourceId, Span};
+use std::fmt::Write;
+
+/// A problem found in a program.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// The source of the error.
+    pub span: Span,
+    /// What is wrong.
+    pub message: String,
+    /// Additional information, such as related definitions.
+    pub notes: Vec<Note>,
+}
+
+/// How serious a diagnostic is.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Severity {
+    /// The program is invalid, and cannot be run.
+    Error,
+    /// The program is valid, but likely does not do what was intended.
+    Warning,
+}
+
+/// Additional information attached to a diagnostic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Note {
+    /// The source the note refers to, if any.
+    pub span: Option<Span>,
+    /// The content of the note.
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic for an error at the span.
+    pub fn error(span: Span, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            span,
+            message: message.into(),
+            notes: vec![],
+        }
+    }
+
+    /// Create a diagnostic for a warning at the span.
+    pub fn warning(span: Span, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(span, message)
+        }
+    }
+
+    /// Whether this diagnostic is an error, rather than a warning.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Attach a note that does not refer to the source.
+    pub fn with_note(mut self, message: impl Into<String>) -> Self {
+        self.notes.push(Note {
+            span: None,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Attach a note referring to the source at the span.
+    pub fn with_note_at(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.notes.push(Note {
+            span: Some(span),
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Render this diagnostic for display, quoting the source it refers to.
+    ///
+    /// `files` are the name and contents of each source file, indexed by [`SourceId`].
+    /// Spans of the prelude are quoted from its own source.
+    pub fn render(&self, files: &[(&str, &str)]) -> String {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let mut out = format!("{}: {}\n", severity, self.message);
+        snippet(&mut out, self.span, files);
+        for note in &self.notes {
+            writeln!(out, "note: {}", note.message).unwrap();
+            if let Some(span) = note.span {
+                snippet(&mut out, span, files);
+            }
+        }
+        out
+    }
+}
+
+/// Quote the first line of the span, underlining the span.
+fn snippet(out: &mut String, span: Span, files: &[(&str, &str)]) {
+    let (name, text) = match files.get(span.source.0 as usize) {
+        Some(&file) => file,
+        None if span.source == SourceId::PRELUDE => ("<prelude>", prelude::SOURCE),
+        None => return,
+    };
+    let start = (span.start as usize).min(text.len());
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+    let line = &text[line_start..line_end];
+    let row = text[..start].matches('\n').count() + 1;
+    let column = text[line_start..start].chars().count() + 1;
+    let end = (span.end as usize).max(start).min(line_end);
+    let width = text[start..end].chars().count().max(1);
+
+    let gutter = " ".repeat(row.to_string().len());
+    writeln!(out, "{}--> {}:{}:{}", gutter, name, row, column).unwrap();
+    writeln!(out, "{} |", gutter).unwrap();
+    writeln!(out, "{} | {}", row, line).unwrap();
+    writeln!(
+        out,
+        "{} | {}{}",
+        gutter,
+        " ".repeat(column - 1),
+        "^".repeat(width)
+    )
+    .unwrap();
+}
@@ -0,0 +1,224 @@
+//! Expressions, which compute values, and the blocks and statements they are built from.
+
+use crate::{
+    items::Item,
+    paths::{Name, Path},
+    patterns::Pattern,
+    HirId, Span,
+};
+
+/// An expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Expr {
+    /// Identifies the expression. Control flow refers to loops and closures by this id.
+    pub id: HirId,
+    /// The span of the expression.
+    pub span: Span,
+    /// What the expression does.
+    pub kind: ExprKind,
+}
+
+/// The kinds of expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExprKind {
+    /// A literal value.
+    Literal(Literal),
+    /// A reference to a named definition or binding.
+    Path(Path),
+    /// A tuple of the values, in order.
+    Tuple(Vec<Expr>),
+    /// A list of the values, in order.
+    List(Vec<Expr>),
+    /// A record value: `Point { x = 1, y = 2 }`.
+    Record {
+        /// The path naming the record type.
+        path: Path,
+        /// The values of the fields, in the order written.
+        fields: Vec<FieldInit>,
+    },
+    /// A function value.
+    Closure(Closure),
+    /// A call of a function value: either named by a path, or computed.
+    Call {
+        /// The called function.
+        callee: Box<Expr>,
+        /// The arguments within the parentheses.
+        arguments: Vec<Argument>,
+        /// The trailing closure, if any.
+        trailing_closure: Option<Box<Expr>>,
+    },
+    /// A call of a method of a value.
+    MethodCall {
+        /// The value whose method is called.
+        receiver: Box<Expr>,
+        /// The name of the method.
+        method: Name,
+        /// The arguments within the parentheses.
+        arguments: Vec<Argument>,
+        /// The trailing closure, if any.
+        trailing_closure: Option<Box<Expr>>,
+    },
+    /// Reads a field of a record.
+    Field {
+        /// The record.
+        receiver: Box<Expr>,
+        /// The name of the field.
+        field: Name,
+    },
+    /// Reads an element of a collection.
+    Index {
+        /// The collection.
+        receiver: Box<Expr>,
+        /// The index.
+        index: Box<Expr>,
+    },
+    /// Evaluates the statements of a block in order.
+    Block(Block),
+    /// Evaluates one of two blocks, by the truth of the condition.
+    ///
+    /// `else if` is lowered to an else block containing only the chained if.
+    If {
+        /// The condition.
+        condition: Box<Expr>,
+        /// The block evaluated if the condition is true.
+        then_branch: Block,
+        /// The block evaluated if the condition is false, if any.
+        else_branch: Option<Block>,
+    },
+    /// Evaluates the first arm whose pattern matches the scrutinee and whose guard holds.
+    Match {
+        /// The matched value.
+        scrutinee: Box<Expr>,
+        /// The arms, in order.
+        arms: Vec<Arm>,
+    },
+    /// Evaluates its body until exited. `while` loops are lowered to this.
+    Loop {
+        /// The label of the loop, if it has one.
+        label: Option<Name>,
+        /// The body of the loop.
+        body: Block,
+    },
+    /// Evaluates its body for each element of the iterable.
+    For {
+        /// The label of the loop, if it has one.
+        label: Option<Name>,
+        /// The pattern bound to each element.
+        pattern: Pattern,
+        /// The iterated value.
+        iterable: Box<Expr>,
+        /// The body of the loop.
+        body: Block,
+    },
+    /// Exits a loop.
+    Break {
+        /// The id of the exited `Loop` or `For` expression.
+        target: HirId,
+        /// The value of the exited loop, if any.
+        value: Option<Box<Expr>>,
+    },
+    /// Continues with the next iteration of a loop.
+    Continue {
+        /// The id of the continued `Loop` or `For` expression.
+        target: HirId,
+    },
+    /// Exits a function.
+    Return {
+        /// The id of the exited function: a `Closure` expression or a function declaration.
+        target: HirId,
+        /// The returned value, if any.
+        value: Option<Box<Expr>>,
+    },
+}
+
+/// A literal value.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Literal {
+    /// An integer, as its decimal digits.
+    Integer(String),
+    /// `true` or `false`.
+    Boolean(bool),
+    /// A character.
+    Character(char),
+    /// The unit value, `()`.
+    Unit,
+}
+
+/// A sequence of statements, optionally followed by an expression giving its value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Block {
+    /// The span of the block.
+    pub span: Span,
+    /// The statements, in order.
+    pub statements: Vec<Stmt>,
+    /// The expression giving the value of the block, if any.
+    pub tail: Option<Box<Expr>>,
+}
+
+/// A statement.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Stmt {
+    /// An expression, evaluated for its effect.
+    Expr(Expr),
+    /// A declaration, in scope throughout the enclosing block.
+    Item(Item),
+}
+
+/// A function expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Closure {
+    /// The label `return` may exit it by, if it has one.
+    ///
+    /// A trailing closure is implicitly labelled with the name of the function it is passed to.
+    pub label: Option<Name>,
+    /// The parameters, always bound positionally.
+    pub parameters: Vec<ClosureParameter>,
+    /// The body.
+    pub body: Block,
+}
+
+/// A parameter of a function expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClosureParameter {
+    /// The span of the parameter.
+    pub span: Span,
+    /// The pattern binding the argument.
+    pub pattern: Pattern,
+    /// The type of the parameter, if given.
+    pub ty: Option<Path>,
+}
+
+/// An argument of a call.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Argument {
+    /// The span of the argument.
+    pub span: Span,
+    /// The label of the bound parameter, if the argument is named.
+    pub name: Option<Name>,
+    /// The value of the argument.
+    pub value: Expr,
+}
+
+/// The value of one field of a record expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldInit {
+    /// The span of the field initializer.
+    pub span: Span,
+    /// The name of the field.
+    pub name: Name,
+    /// The value of the field.
+    pub value: Expr,
+}
+
+/// An arm of a match expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Arm {
+    /// The span of the arm.
+    pub span: Span,
+    /// The pattern matched against the scrutinee.
+    pub pattern: Pattern,
+    /// The condition that must also hold, if any.
+    pub guard: Option<Expr>,
+    /// The value of the match if this arm is taken.
+    pub body: Expr,
+}
@@ -0,0 +1,203 @@
+//! Items are declarations, which define names in the scope that contains them.
+
+use crate::{
+    expressions::{Argument, Block, Expr},
+    paths::{Name, Path},
+    patterns::Pattern,
+    HirId, Span,
+};
+
+/// A whole program: its prelude and root module, and the code run when it starts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Program {
+    /// The prelude, a module without a name whose items are in scope in every module.
+    pub prelude: Module,
+    /// The root module.
+    pub root: Module,
+    /// The statements of the root that are not declarations, as a closure without parameters.
+    ///
+    /// It is in the scope of the root module, and `return` may exit it.
+    pub main: Expr,
+    /// The number of [`HirId`]s lowering handed out, all of which are less than this.
+    ///
+    /// Passes that add nodes to the program take their ids from here on.
+    pub next_id: u32,
+}
+
+/// A declaration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum Item {
+    Function(Function),
+    Type(TypeDef),
+    Enum(EnumDef),
+    Module(Module),
+    Import(Import),
+}
+
+/// A module, which is a namespace of items.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Module {
+    /// Identifies the module.
+    pub id: HirId,
+    /// The span of the declaration, or of the whole file for the root module.
+    pub span: Span,
+    /// The name of the module, or `None` for the root module.
+    pub name: Option<Name>,
+    /// The documentation of the module.
+    pub docs: Vec<String>,
+    /// The attributes of the module.
+    pub attributes: Vec<Attribute>,
+    /// The items of the module, in the order declared.
+    pub items: Vec<Item>,
+}
+
+/// A function declaration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Function {
+    /// Identifies the function. A bare `return` exits the innermost function declaration.
+    pub id: HirId,
+    /// The span of the declaration.
+    pub span: Span,
+    /// The name of the function.
+    pub name: Name,
+    /// The documentation of the function.
+    pub docs: Vec<String>,
+    /// The attributes of the function.
+    pub attributes: Vec<Attribute>,
+    /// The generic type parameters of the function.
+    pub generics: Vec<Generic>,
+    /// The parameters, in the order declared.
+    pub parameters: Vec<Parameter>,
+    /// The declared return type, if any.
+    pub return_type: Option<Path>,
+    /// The label `return` may also exit the function by, if the body has one.
+    pub label: Option<Name>,
+    /// The body of the function.
+    pub body: Block,
+}
+
+/// A generic type parameter of a function declaration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Generic {
+    /// Identifies the type parameter.
+    pub id: HirId,
+    /// The name of the type parameter.
+    pub name: Name,
+}
+
+/// A parameter of a function declaration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Parameter {
+    /// The span of the parameter.
+    pub span: Span,
+    /// The label a call may bind the parameter by, if it has one.
+    pub label: Option<Name>,
+    /// How a call may bind the parameter.
+    pub kind: ParameterKind,
+    /// The type of the parameter.
+    pub ty: Path,
+    /// The value of the parameter when a call does not bind it, if any.
+    pub default: Option<Expr>,
+    /// The pattern binding the argument in the body.
+    ///
+    /// This is the label, unless the body names its own arguments.
+    pub pattern: Pattern,
+}
+
+/// How a call may bind a parameter.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ParameterKind {
+    /// An unlabelled parameter, bound only positionally.
+    Positional,
+    /// A labelled parameter, bound positionally or by name.
+    Labelled,
+    /// A labelled parameter after `*` or a variadic parameter, bound only by name.
+    KeywordOnly,
+    /// A parameter bound to the list of the remaining positional arguments.
+    Variadic,
+}
+
+/// An attribute of a declaration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attribute {
+    /// The span of the attribute.
+    pub span: Span,
+    /// The path naming the attribute.
+    pub path: Path,
+    /// The arguments of the attribute.
+    pub arguments: Vec<Argument>,
+}
+
+/// A record type declaration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypeDef {
+    /// Identifies the type.
+    pub id: HirId,
+    /// The span of the declaration.
+    pub span: Span,
+    /// The name of the type.
+    pub name: Name,
+    /// The documentation of the type.
+    pub docs: Vec<String>,
+    /// The attributes of the type.
+    pub attributes: Vec<Attribute>,
+    /// The fields of the record.
+    pub fields: Vec<Field>,
+}
+
+/// An enum declaration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnumDef {
+    /// Identifies the enum.
+    pub id: HirId,
+    /// The span of the declaration.
+    pub span: Span,
+    /// The name of the enum.
+    pub name: Name,
+    /// The documentation of the enum.
+    pub docs: Vec<String>,
+    /// The attributes of the enum.
+    pub attributes: Vec<Attribute>,
+    /// The variants of the enum.
+    pub variants: Vec<Variant>,
+}
+
+/// A variant of an enum.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Variant {
+    /// Identifies the variant.
+    pub id: HirId,
+    /// The span of the variant.
+    pub span: Span,
+    /// The name of the variant.
+    pub name: Name,
+    /// The fields of the variant, in order.
+    pub fields: Vec<Field>,
+}
+
+/// A field of a record type or enum variant.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Field {
+    /// The span of the field.
+    pub span: Span,
+    /// The name of the field.
+    pub name: Name,
+    /// The type of the field.
+    pub ty: Path,
+}
+
+/// An import of a single name, lowered from a use declaration.
+///
+/// `use a::{b, c as d};` is lowered to the imports `a::b` as `b` and `a::c` as `d`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Import {
+    /// Identifies the import.
+    pub id: HirId,
+    /// The span of the imported tree.
+    pub span: Span,
+    /// The path of the imported definition.
+    pub path: Path,
+    /// The name the definition is imported as.
+    pub name: Name,
+}
@@ -0,0 +1,84 @@
+//! # High-level Intermediate Representation for the Nafi programming language.
+//!
+//! This is the third representation of source code as it gets processed,
+//! after the Lossless Syntax Tree and the Abstract Syntax Tree (see `nafi_ast`).
+//!
+//! The HIR is lowered from the AST, and is where the program stops being text.
+//! Every node owns its data and refers to the source only through a [`Span`],
+//! so later passes never need to consult the syntax tree or the source string.
+//!
+//! Lowering makes implicit meaning explicit:
+//!
+//! - `break`, `continue`, and `return` refer directly to the node they exit,
+//!   including the implicit labels of trailing closures.
+//! - `while` loops are rewritten as `loop`s that `break` once their condition is false.
+//! - `use` declarations are flattened into one import per name.
+//! - The arguments of a function declaration are bound by its parameters,
+//!   whether by their label or by the arguments of its body.
+//! - [The prelude](prelude) is lowered into every program, beside its root module.
+//!
+//! Nodes that later passes attach information to (such as definitions, expressions,
+//! and bindings) carry a [`HirId`], unique within a program.
+//! [Name resolution](resolve) is the first such pass, resolving every path to a definition,
+//! followed by [type checking](check), inferring the type of every expression
+//! and selecting the function declaration each call refers to by [overload resolution](overload).
+//! Finally, [calls are desugared](desugar) so that all of their arguments are positional.
+
+#![warn(missing_docs)]
+
+pub mod check;
+pub mod desugar;
+pub mod diagnostics;
+pub mod expressions;
+pub mod items;
+pub mod lower;
+pub mod overload;
+pub mod paths;
+pub mod patterns;
+pub mod prelude;
+pub mod resolve;
+pub mod types;
+pub mod visit;
+
+mod span;
+pub use self::span::{SourceId, Span};
+
+/// Identifies a node of the HIR, unique within a lowered program.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct HirId(pub u32);
+
+impl HirId {
+    /// Stands in for the node an erroneous reference would refer to,
+    /// such as the target of a `break` outside of a loop.
+    ///
+    /// A diagnostic is always reported where this is used.
+    pub const ERROR: HirId = HirId(u32::MAX);
+}
+
+/// A lowered program, with the results of every pass over it.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    /// The program, with its calls desugared.
+    pub program: items::Program,
+    /// The definition each path refers to.
+    pub resolutions: resolve::Resolutions,
+    /// The type of each expression, and the overload each call selects.
+    pub typing: check::Typing,
+}
+
+/// Run every pass after lowering over the program: resolve its names, check its types,
+/// and desugar its calls.
+///
+/// The program may only be run if none of the diagnostics is an error.
+pub fn analyze(mut program: items::Program) -> (Analysis, Vec<diagnostics::Diagnostic>) {
+    let (mut resolutions, mut diagnostics) = resolve::resolve(&program);
+    let (mut typing, type_errors) = check::check(&program, &resolutions);
+    diagnostics.extend(type_errors);
+    desugar::desugar_calls(&mut program, &mut resolutions, &mut typing);
+    let analysis = Analysis {
+        program,
+        resolutions,
+        typing,
+    };
+    (analysis, diagnostics)
+}
@@ -0,0 +1,1018 @@
+//! Lowering from the AST to the HIR.
+//!
+//! Lowering reports the errors it finds as diagnostics rather than stopping,
+//! so that as many problems as possible are reported at once.
+
+use crate::{
+    diagnostics::Diagnostic,
+    expressions::{
+        Argument, Arm, Block, Closure, ClosureParameter, Expr, ExprKind, FieldInit, Literal, Stmt,
+    },
+    items::{
+        Attribute, EnumDef, Field, Function, Generic, Import, Item, Module, Parameter,
+        ParameterKind, Program, TypeDef, Variant,
+    },
+    paths::{Name, Path, PathSegment},
+    patterns::{FieldPattern, Pattern, PatternKind},
+    prelude, HirId, SourceId, Span,
+};
+use nafi_ast::{
+    attributes, containers, control, functions, modules, paths, patterns, terminals, types, Spanned,
+};
+
+/// A source file of a program, as the body of a module.
+#[derive(Copy, Clone, Debug)]
+pub struct ModuleSource<'a, 'i> {
+    /// Identifies the file in spans.
+    pub source: SourceId,
+    /// The names of the modules leading to the module the file is the body of.
+    ///
+    /// This is empty for the root file.
+    pub module_path: &'a [String],
+    /// The parsed file.
+    pub file: &'a modules::ModuleFile<'i>,
+}
+
+/// Lower a program from its source files, which must include a root file.
+///
+/// A module declared without a body is lowered from the file with its module path.
+pub fn lower_program(files: &[ModuleSource<'_, '_>]) -> (Program, Vec<Diagnostic>) {
+    let root = *files
+        .iter()
+        .find(|file| file.module_path.is_empty())
+        .expect("program without a root file");
+    let mut lowerer = Lowerer::new(files, root.source);
+    let prelude = lowerer.prelude();
+    let span = lowerer.span(root.file);
+    let main_id = lowerer.id();
+    lowerer.targets.push(Target::Function {
+        label: None,
+        id: main_id,
+        declaration: false,
+    });
+    let (items, statements) = lowerer.module_body(root.file.statements(), true);
+    lowerer.targets.pop();
+    let program = Program {
+        prelude,
+        root: Module {
+            id: lowerer.id(),
+            span,
+            name: None,
+            docs: vec![],
+            attributes: vec![],
+            items,
+        },
+        main: main_closure(main_id, span, statements),
+        next_id: lowerer.next_id,
+    };
+    (program, lowerer.diagnostics)
+}
+
+/// Lower a program consisting of a single function expression, such as one entered in a REPL.
+///
+/// The function expression is the main function of the program, and its root module is empty.
+/// Declarations in it are local to its body.
+pub fn lower_script(
+    source: SourceId,
+    function: &functions::FunctionExpression<'_>,
+) -> (Program, Vec<Diagnostic>) {
+    let mut lowerer = Lowerer::new(&[], source);
+    let prelude = lowerer.prelude();
+    let span = lowerer.span(function);
+    let main = lowerer.closure(function, None);
+    let program = Program {
+        prelude,
+        root: Module {
+            id: lowerer.id(),
+            span,
+            name: None,
+            docs: vec![],
+            attributes: vec![],
+            items: vec![],
+        },
+        main,
+        next_id: lowerer.next_id,
+    };
+    (program, lowerer.diagnostics)
+}
+
+fn main_closure(id: HirId, span: Span, statements: Vec<Stmt>) -> Expr {
+    Expr {
+        id,
+        span,
+        kind: ExprKind::Closure(Closure {
+            label: None,
+            parameters: vec![],
+            body: Block {
+                span,
+                statements,
+                tail: None,
+            },
+        }),
+    }
+}
+
+/// A construct that control flow expressions may exit.
+enum Target<'i> {
+    Loop {
+        label: Option<&'i str>,
+        id: HirId,
+    },
+    Function {
+        label: Option<&'i str>,
+        id: HirId,
+        declaration: bool,
+    },
+}
+
+struct Lowerer<'a, 'i> {
+    files: &'a [ModuleSource<'a, 'i>],
+    /// The file currently being lowered.
+    source: SourceId,
+    /// The path of the module currently being lowered.
+    module_path: Vec<String>,
+    /// The enclosing loops and functions, innermost last.
+    targets: Vec<Target<'i>>,
+    next_id: u32,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a, 'i> Lowerer<'a, 'i> {
+    fn new(files: &'a [ModuleSource<'a, 'i>], source: SourceId) -> Self {
+        Lowerer {
+            files,
+            source,
+            module_path: vec![],
+            targets: vec![],
+            next_id: 0,
+            diagnostics: vec![],
+        }
+    }
+
+    fn id(&mut self) -> HirId {
+        self.next_id += 1;
+        HirId(self.next_id - 1)
+    }
+
+    /// The span of a node, excluding any whitespace the parser included after it.
+    fn span(&self, node: &impl Spanned) -> Span {
+        let span = node.span();
+        let length = span.as_str().trim_end().len() as u32;
+        Span::new(self.source, span.start_byte(), span.start_byte() + length)
+    }
+
+    fn error(&mut self, span: Span, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic::error(span, message));
+    }
+
+    fn name(&self, identifier: &terminals::Identifier<'i>) -> Name {
+        Name {
+            span: self.span(identifier),
+            text: identifier.as_str().to_string(),
+        }
+    }
+
+    // ~~~ items ~~~ //
+
+    /// Lower the statements of a module into its items,
+    /// and the statements that are not declarations, which only the root module may have.
+    fn module_body(
+        &mut self,
+        statements: &[containers::Statement<'i>],
+        root: bool,
+    ) -> (Vec<Item>, Vec<Stmt>) {
+        let mut items = vec![];
+        let mut rest = vec![];
+        for statement in statements {
+            match statement {
+                containers::Statement::Expression(expression) => {
+                    let expression = self.expression(expression);
+                    if !root {
+                        self.error(
+                            expression.span,
+                            "expressions are only allowed in the root module",
+                        );
+                    }
+                    rest.push(Stmt::Expr(expression));
+                }
+                _ => self.items(statement, &mut items),
+            }
+        }
+        (items, rest)
+    }
+
+    /// Lower a declaration statement into the items it declares.
+    fn items(&mut self, statement: &containers::Statement<'i>, items: &mut Vec<Item>) {
+        match statement {
+            containers::Statement::Expression(_) => unreachable!("expression is not a declaration"),
+            containers::Statement::FunctionDeclaration(declaration) => {
+                items.push(Item::Function(self.function(declaration)))
+            }
+            containers::Statement::TypeDeclaration(declaration) => {
+                items.push(Item::Type(self.type_def(declaration)))
+            }
+            containers::Statement::EnumDeclaration(declaration) => {
+                items.push(Item::Enum(self.enum_def(declaration)))
+            }
+            containers::Statement::ModuleDeclaration(declaration) => {
+                items.push(Item::Module(self.module(declaration)))
+            }
+            containers::Statement::UseDeclaration(declaration) => {
+                self.imports(declaration.tree(), None, items)
+            }
+        }
+    }
+
+    fn docs(&self, docs: &[attributes::DocComment<'i>]) -> Vec<String> {
+        docs.iter().map(|doc| doc.text().to_string()).collect()
+    }
+
+    fn attributes(&mut self, attributes: &[attributes::Attribute<'i>]) -> Vec<Attribute> {
+        attributes
+            .iter()
+            .map(|attribute| Attribute {
+                span: self.span(attribute),
+                path: self.path(attribute.path()),
+                arguments: self.arguments(attribute.arguments()),
+            })
+            .collect()
+    }
+
+    fn prelude(&mut self) -> Module {
+        let file = nafi_parser::parse_module(prelude::SOURCE).expect("the prelude parses");
+        let source = std::mem::replace(&mut self.source, SourceId::PRELUDE);
+        let span = self.span(&file);
+        let (items, statements) = self.module_body(file.statements(), false);
+        debug_assert!(statements.is_empty());
+        self.source = source;
+        Module {
+            id: self.id(),
+            span,
+            name: None,
+            docs: vec![],
+            attributes: vec![],
+            items,
+        }
+    }
+
+    fn module(&mut self, declaration: &modules::ModuleDeclaration<'i>) -> Module {
+        let id = self.id();
+        let span = self.span(declaration);
+        let name = self.name(declaration.name());
+        let docs = self.docs(declaration.docs());
+        let attributes = self.attributes(declaration.attributes());
+        self.module_path.push(name.text.clone());
+        // Modules are not evaluated where they are declared.
+        let targets = std::mem::take(&mut self.targets);
+        let items = match declaration.body() {
+            Some(body) => self.module_body(body.statements(), false).0,
+            None => match self
+                .files
+                .iter()
+                .find(|f| f.module_path == &self.module_path[..])
+            {
+                Some(&file) => {
+                    let source = std::mem::replace(&mut self.source, file.source);
+                    let items = self.module_body(file.file.statements(), false).0;
+                    self.source = source;
+                    items
+                }
+                None => {
+                    let message = format!("no source file for module `{}`", name.text);
+                    self.error(span, message);
+                    vec![]
+                }
+            },
+        };
+        self.targets = targets;
+        self.module_path.pop();
+        Module {
+            id,
+            span,
+            name: Some(name),
+            docs,
+            attributes,
+            items,
+        }
+    }
+
+    /// Flatten a use tree into one import per imported name.
+    fn imports(
+        &mut self,
+        tree: &modules::UseTree<'i>,
+        prefix: Option<&Path>,
+        items: &mut Vec<Item>,
+    ) {
+        let span = self.span(tree);
+        let mut path = self.path(tree.path());
+        if let Some(prefix) = prefix {
+            let mut segments = prefix.segments.clone();
+            segments.append(&mut path.segments);
+            path = Path {
+                id: path.id,
+                span: prefix.span.to(path.span),
+                global: prefix.global,
+                segments,
+            };
+        }
+        match (tree.group(), tree.alias()) {
+            (Some(group), _) => {
+                for tree in group.trees() {
+                    self.imports(tree, Some(&path), items);
+                }
+            }
+            (None, alias) => {
+                let name = match alias {
+                    Some(alias) => self.name(alias.name()),
+                    None => path.last().name.clone(),
+                };
+                items.push(Item::Import(Import {
+                    id: self.id(),
+                    span,
+                    path,
+                    name,
+                }));
+            }
+        }
+    }
+
+    fn type_def(&mut self, declaration: &types::TypeDeclaration<'i>) -> TypeDef {
+        TypeDef {
+            id: self.id(),
+            span: self.span(declaration),
+            name: self.name(declaration.name()),
+            docs: self.docs(declaration.docs()),
+            attributes: self.attributes(declaration.attributes()),
+            fields: self.fields(declaration.definition().fields()),
+        }
+    }
+
+    fn enum_def(&mut self, declaration: &types::EnumDeclaration<'i>) -> EnumDef {
+        let id = self.id();
+        let variants = declaration
+            .variants()
+            .iter()
+            .map(|variant| Variant {
+                id: self.id(),
+                span: self.span(variant),
+                name: self.name(variant.name()),
+                fields: self.fields(variant.fields()),
+            })
+            .collect();
+        EnumDef {
+            id,
+            span: self.span(declaration),
+            name: self.name(declaration.name()),
+            docs: self.docs(declaration.docs()),
+            attributes: self.attributes(declaration.attributes()),
+            variants,
+        }
+    }
+
+    fn fields(&mut self, fields: &[types::RecordField<'i>]) -> Vec<Field> {
+        fields
+            .iter()
+            .map(|field| Field {
+                span: self.span(field),
+                name: self.name(field.name()),
+                ty: self.path(field.r#type()),
+            })
+            .collect()
+    }
+
+    fn function(&mut self, declaration: &functions::FunctionDeclaration<'i>) -> Function {
+        let id = self.id();
+        let span = self.span(declaration);
+        let body = declaration.body();
+        let label = body.label().map(|label| label.name());
+
+        let declared: Vec<_> = declaration
+            .arguments()
+            .iter()
+            .map(|argument| (argument, false))
+            .chain(
+                declaration
+                    .keyword_only()
+                    .into_iter()
+                    .flat_map(|keyword_only| keyword_only.arguments())
+                    .map(|argument| (argument, true)),
+            )
+            .collect();
+        let bindings = body.arguments();
+        if !bindings.is_empty() && bindings.len() != declared.len() {
+            let message = format!(
+                "the body of `{}` binds {} arguments, but it declares {}",
+                declaration.name().as_str(),
+                bindings.len(),
+                declared.len(),
+            );
+            self.error(self.span(body), message);
+        }
+
+        // Parameter defaults are evaluated in the function, not where it is declared.
+        let targets = std::mem::replace(
+            &mut self.targets,
+            vec![Target::Function {
+                label: label.map(terminals::Identifier::as_str),
+                id,
+                declaration: true,
+            }],
+        );
+        let mut after_variadic = false;
+        let mut parameters = vec![];
+        for (index, &(argument, keyword_only)) in declared.iter().enumerate() {
+            let span = self.span(argument);
+            let parameter_label = argument.name().map(|name| self.name(name));
+            let kind = if argument.variadic().is_some() {
+                after_variadic = true;
+                ParameterKind::Variadic
+            } else if keyword_only || after_variadic {
+                ParameterKind::KeywordOnly
+            } else if parameter_label.is_some() {
+                ParameterKind::Labelled
+            } else {
+                ParameterKind::Positional
+            };
+            if kind == ParameterKind::KeywordOnly && parameter_label.is_none() {
+                self.error(span, "keyword-only arguments must be labelled");
+            }
+            let pattern = match bindings.get(index) {
+                Some(binding) if bindings.len() == declared.len() => {
+                    if let Some(r#type) = binding.r#type() {
+                        let message = "the type of this argument is given by the declaration";
+                        self.error(self.span(r#type), message);
+                    }
+                    self.pattern(binding.pattern())
+                }
+                _ => Pattern {
+                    id: self.id(),
+                    span,
+                    kind: match &parameter_label {
+                        Some(label) => PatternKind::Binding(label.clone()),
+                        None => PatternKind::Wildcard,
+                    },
+                },
+            };
+            parameters.push(Parameter {
+                span,
+                label: parameter_label,
+                kind,
+                ty: self.path(argument.r#type()),
+                default: argument.default().map(|default| self.expression(default)),
+                pattern,
+            });
+        }
+        let block = self.block(body, body.statements(), body.tail_expression());
+        self.targets = targets;
+
+        Function {
+            id,
+            span,
+            name: self.name(declaration.name()),
+            docs: self.docs(declaration.docs()),
+            attributes: self.attributes(declaration.attributes()),
+            generics: declaration
+                .generics()
+                .iter()
+                .map(|generic| Generic {
+                    id: self.id(),
+                    name: self.name(generic.name()),
+                })
+                .collect(),
+            parameters,
+            return_type: declaration.r#return().map(|r#return| self.path(r#return)),
+            label: label.map(|label| self.name(label)),
+            body: block,
+        }
+    }
+
+    // ~~~ expressions ~~~ //
+
+    fn block(
+        &mut self,
+        node: &impl Spanned,
+        statements: &[containers::Statement<'i>],
+        tail: Option<&containers::Expression<'i>>,
+    ) -> Block {
+        let mut lowered = vec![];
+        for statement in statements {
+            match statement {
+                containers::Statement::Expression(expression) => {
+                    lowered.push(Stmt::Expr(self.expression(expression)))
+                }
+                _ => {
+                    let mut items = vec![];
+                    self.items(statement, &mut items);
+                    lowered.extend(items.into_iter().map(Stmt::Item));
+                }
+            }
+        }
+        Block {
+            span: self.span(node),
+            statements: lowered,
+            tail: tail.map(|tail| Box::new(self.expression(tail))),
+        }
+    }
+
+    fn ast_block(&mut self, block: &control::Block<'i>) -> Block {
+        self.block(block, block.statements(), block.tail_expression())
+    }
+
+    /// A block containing only the expression.
+    fn wrap(expression: Expr) -> Block {
+        Block {
+            span: expression.span,
+            statements: vec![],
+            tail: Some(Box::new(expression)),
+        }
+    }
+
+    fn closure(
+        &mut self,
+        function: &functions::FunctionExpression<'i>,
+        implicit_label: Option<&terminals::Identifier<'i>>,
+    ) -> Expr {
+        let id = self.id();
+        let span = self.span(function);
+        let label = function
+            .label()
+            .map(|label| label.name())
+            .or(implicit_label);
+        let parameters = function
+            .arguments()
+            .iter()
+            .map(|argument| ClosureParameter {
+                span: self.span(argument),
+                pattern: self.pattern(argument.pattern()),
+                ty: argument.r#type().map(|r#type| self.path(r#type)),
+            })
+            .collect();
+        self.targets.push(Target::Function {
+            label: label.map(terminals::Identifier::as_str),
+            id,
+            declaration: false,
+        });
+        let body = self.block(function, function.statements(), function.tail_expression());
+        self.targets.pop();
+        Expr {
+            id,
+            span,
+            kind: ExprKind::Closure(Closure {
+                label: label.map(|label| self.name(label)),
+                parameters,
+                body,
+            }),
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[functions::FunctionCallArgument<'i>]) -> Vec<Argument> {
+        let arguments: Vec<_> = arguments
+            .iter()
+            .map(|argument| Argument {
+                span: self.span(argument),
+                name: argument.name().map(|name| self.name(name)),
+                value: self.expression(argument.value()),
+            })
+            .collect();
+        let labels: Vec<_> = arguments
+            .iter()
+            .filter_map(|argument| argument.name.as_ref())
+            .collect();
+        for (index, label) in labels.iter().enumerate() {
+            if let Some(first) = labels[..index]
+                .iter()
+                .find(|first| first.text == label.text)
+            {
+                let message = format!("the argument `{}` is given more than once", label.text);
+                let diagnostic = Diagnostic::error(label.span, message)
+                    .with_note_at(first.span, format!("`{}` is first given here", label.text));
+                self.diagnostics.push(diagnostic);
+            }
+        }
+        arguments
+    }
+
+    fn expressions(&mut self, expressions: &[containers::Expression<'i>]) -> Vec<Expr> {
+        expressions
+            .iter()
+            .map(|expression| self.expression(expression))
+            .collect()
+    }
+
+    fn expression(&mut self, expression: &containers::Expression<'i>) -> Expr {
+        use nafi_ast::containers::Expression as E;
+        let span = self.span(expression);
+        let kind = match expression {
+            E::FunctionExpression(function) => return self.closure(function, None),
+            E::IfExpression(expression) => self.if_expression(expression),
+            E::MatchExpression(expression) => ExprKind::Match {
+                scrutinee: Box::new(self.expression(expression.scrutinee())),
+                arms: expression
+                    .arms()
+                    .iter()
+                    .map(|arm| Arm {
+                        span: self.span(arm),
+                        pattern: self.pattern(arm.pattern()),
+                        guard: arm.guard().map(|guard| self.expression(guard.condition())),
+                        body: match arm.body() {
+                            control::MatchArmBody::Block(block) => {
+                                let block = self.ast_block(block);
+                                Expr {
+                                    id: self.id(),
+                                    span: block.span,
+                                    kind: ExprKind::Block(block),
+                                }
+                            }
+                            control::MatchArmBody::Expression(expression) => {
+                                self.expression(expression)
+                            }
+                        },
+                    })
+                    .collect(),
+            },
+            E::WhileExpression(expression) => return self.while_expression(expression),
+            E::LoopExpression(expression) => {
+                let id = self.id();
+                let label = expression.label().map(|label| label.name());
+                self.targets.push(Target::Loop {
+                    label: label.map(terminals::Identifier::as_str),
+                    id,
+                });
+                let body = self.ast_block(expression.body());
+                self.targets.pop();
+                return Expr {
+                    id,
+                    span,
+                    kind: ExprKind::Loop {
+                        label: label.map(|label| self.name(label)),
+                        body,
+                    },
+                };
+            }
+            E::ForExpression(expression) => {
+                let id = self.id();
+                let label = expression.label().map(|label| label.name());
+                let pattern = self.pattern(expression.pattern());
+                let iterable = Box::new(self.expression(expression.iterable()));
+                self.targets.push(Target::Loop {
+                    label: label.map(terminals::Identifier::as_str),
+                    id,
+                });
+                let body = self.ast_block(expression.body());
+                self.targets.pop();
+                return Expr {
+                    id,
+                    span,
+                    kind: ExprKind::For {
+                        label: label.map(|label| self.name(label)),
+                        pattern,
+                        iterable,
+                        body,
+                    },
+                };
+            }
+            E::BreakExpression(expression) => ExprKind::Break {
+                target: self.loop_target(span, "break", expression.label()),
+                value: expression
+                    .value()
+                    .map(|value| Box::new(self.expression(value))),
+            },
+            E::ContinueExpression(expression) => ExprKind::Continue {
+                target: self.loop_target(span, "continue", expression.label()),
+            },
+            E::ReturnExpression(expression) => ExprKind::Return {
+                target: self.function_target(span, expression.label()),
+                value: expression
+                    .value()
+                    .map(|value| Box::new(self.expression(value))),
+            },
+            E::RecordExpression(expression) => ExprKind::Record {
+                path: self.path(expression.r#type()),
+                fields: expression
+                    .fields()
+                    .iter()
+                    .map(|field| FieldInit {
+                        span: self.span(field),
+                        name: self.name(field.name()),
+                        value: self.expression(field.value()),
+                    })
+                    .collect(),
+            },
+            E::TupleExpression(expression) => {
+                ExprKind::Tuple(self.expressions(expression.elements()))
+            }
+            E::ListExpression(expression) => {
+                ExprKind::List(self.expressions(expression.elements()))
+            }
+            E::FunctionCall(call) => {
+                let path = self.path(call.path());
+                let callee = Expr {
+                    id: self.id(),
+                    span: path.span,
+                    kind: ExprKind::Path(path),
+                };
+                let implicit_label = call.path().segments().last().map(|segment| segment.name());
+                ExprKind::Call {
+                    callee: Box::new(callee),
+                    arguments: self.arguments(call.arguments()),
+                    trailing_closure: call
+                        .tail_closure()
+                        .map(|closure| Box::new(self.closure(closure, implicit_label))),
+                }
+            }
+            E::Path(path) => ExprKind::Path(self.path(path)),
+            E::Identifier(identifier) => ExprKind::Path(self.name_path(identifier)),
+            E::IntegerLiteral(literal) => {
+                ExprKind::Literal(Literal::Integer(literal.digits().to_string()))
+            }
+            E::BooleanLiteral(literal) => ExprKind::Literal(Literal::Boolean(literal.value())),
+            E::CharacterLiteral(literal) => ExprKind::Literal(self.character(literal)),
+            E::UnitLiteral(_) => ExprKind::Literal(Literal::Unit),
+            E::MemberAccess(access) => ExprKind::Field {
+                receiver: Box::new(self.expression(access.receiver())),
+                field: self.name(access.member()),
+            },
+            E::MethodCall(call) => ExprKind::MethodCall {
+                receiver: Box::new(self.expression(call.receiver())),
+                method: self.name(call.method()),
+                arguments: self.arguments(call.arguments()),
+                trailing_closure: call
+                    .tail_closure()
+                    .map(|closure| Box::new(self.closure(closure, Some(call.method())))),
+            },
+            E::CallExpression(call) => ExprKind::Call {
+                callee: Box::new(self.expression(call.callee())),
+                arguments: self.arguments(call.arguments()),
+                trailing_closure: call
+                    .tail_closure()
+                    .map(|closure| Box::new(self.closure(closure, None))),
+            },
+            E::IndexExpression(index) => ExprKind::Index {
+                receiver: Box::new(self.expression(index.receiver())),
+                index: Box::new(self.expression(index.index())),
+            },
+        };
+        Expr {
+            id: self.id(),
+            span,
+            kind,
+        }
+    }
+
+    fn if_expression(&mut self, expression: &control::IfExpression<'i>) -> ExprKind {
+        ExprKind::If {
+            condition: Box::new(self.expression(expression.condition())),
+            then_branch: self.ast_block(expression.then_branch()),
+            else_branch: expression
+                .else_clause()
+                .map(|else_clause| match else_clause.branch() {
+                    control::ElseBranch::Block(block) => self.ast_block(block),
+                    control::ElseBranch::IfExpression(expression) => {
+                        let kind = self.if_expression(expression);
+                        Lowerer::wrap(Expr {
+                            id: self.id(),
+                            span: self.span(expression),
+                            kind,
+                        })
+                    }
+                }),
+        }
+    }
+
+    /// Lower `while condition { body }` to `loop { if condition { body } else { break } }`.
+    fn while_expression(&mut self, expression: &control::WhileExpression<'i>) -> Expr {
+        let id = self.id();
+        let span = self.span(expression);
+        let label = expression.label().map(|label| label.name());
+        self.targets.push(Target::Loop {
+            label: label.map(terminals::Identifier::as_str),
+            id,
+        });
+        let condition = self.expression(expression.condition());
+        let body = self.ast_block(expression.body());
+        self.targets.pop();
+        let exit = Expr {
+            id: self.id(),
+            span: condition.span,
+            kind: ExprKind::Break {
+                target: id,
+                value: None,
+            },
+        };
+        let branch = Expr {
+            id: self.id(),
+            span,
+            kind: ExprKind::If {
+                condition: Box::new(condition),
+                then_branch: body,
+                else_branch: Some(Lowerer::wrap(exit)),
+            },
+        };
+        Expr {
+            id,
+            span,
+            kind: ExprKind::Loop {
+                label: label.map(|label| self.name(label)),
+                body: Lowerer::wrap(branch),
+            },
+        }
+    }
+
+    /// The loop a `break` or `continue` exits, within the innermost function.
+    fn loop_target(
+        &mut self,
+        span: Span,
+        keyword: &str,
+        label: Option<&control::LabelReference<'i>>,
+    ) -> HirId {
+        let wanted = label.map(|label| label.name().as_str());
+        for target in self.targets.iter().rev() {
+            match *target {
+                Target::Loop { label, id } if wanted.is_none() || wanted == label => return id,
+                Target::Loop { .. } => {}
+                Target::Function { .. } => break,
+            }
+        }
+        match label {
+            Some(label) => {
+                let message = format!("use of undeclared label `{}`", label.span().as_str());
+                self.error(self.span(label), message)
+            }
+            None => self.error(span, format!("`{}` outside of a loop", keyword)),
+        }
+        HirId::ERROR
+    }
+
+    /// The function a `return` exits.
+    ///
+    /// A bare `return` exits the innermost function declaration,
+    /// or the main function if it is not in one.
+    fn function_target(
+        &mut self,
+        span: Span,
+        label: Option<&control::LabelReference<'i>>,
+    ) -> HirId {
+        let wanted = label.map(|label| label.name().as_str());
+        let mut outermost = None;
+        for target in self.targets.iter().rev() {
+            if let Target::Function {
+                label,
+                id,
+                declaration,
+            } = *target
+            {
+                match wanted {
+                    Some(_) if wanted == label => return id,
+                    None if declaration => return id,
+                    _ => outermost = Some(id),
+                }
+            }
+        }
+        match (label, outermost) {
+            (None, Some(id)) => return id,
+            (Some(label), _) => {
+                let message = format!("use of undeclared label `{}`", label.span().as_str());
+                self.error(self.span(label), message)
+            }
+            (None, None) => self.error(span, "`return` outside of a function"),
+        }
+        HirId::ERROR
+    }
+
+    fn character(&mut self, literal: &terminals::CharacterLiteral<'i>) -> Literal {
+        match literal.value() {
+            Some(value) => Literal::Character(value),
+            None => {
+                self.error(self.span(literal), "invalid unicode escape");
+                Literal::Character(std::char::REPLACEMENT_CHARACTER)
+            }
+        }
+    }
+
+    // ~~~ paths and patterns ~~~ //
+
+    fn path(&mut self, path: &paths::Path<'i>) -> Path {
+        Path {
+            id: self.id(),
+            span: self.span(path),
+            global: path.span().as_str().starts_with("::"),
+            segments: path
+                .segments()
+                .iter()
+                .map(|segment| PathSegment {
+                    span: self.span(segment),
+                    name: self.name(segment.name()),
+                    arguments: segment
+                        .arguments()
+                        .iter()
+                        .map(|argument| self.type_argument(argument))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn name_path(&mut self, identifier: &terminals::Identifier<'i>) -> Path {
+        let name = self.name(identifier);
+        Path {
+            id: self.id(),
+            span: name.span,
+            global: false,
+            segments: vec![PathSegment {
+                span: name.span,
+                name,
+                arguments: vec![],
+            }],
+        }
+    }
+
+    /// Type arguments are parsed as expressions, but must be paths.
+    ///
+    /// Any other argument is reported, and lowered to [an erroneous path](Path::is_error)
+    /// so that the arity of the type is still checked against what was written.
+    fn type_argument(&mut self, argument: &containers::Expression<'i>) -> Path {
+        match argument {
+            containers::Expression::Path(path) => return self.path(path),
+            containers::Expression::Identifier(identifier) => return self.name_path(identifier),
+            containers::Expression::TupleExpression(_) => {
+                self.diagnostics.push(
+                    Diagnostic::error(self.span(argument), "expected a type, found a tuple")
+                        .with_note("tuple types are written `Tuple[A, B]`"),
+                );
+            }
+            _ => self.error(self.span(argument), "expected a type"),
+        }
+        let span = self.span(argument);
+        Path {
+            id: self.id(),
+            span,
+            global: false,
+            segments: vec![PathSegment {
+                span,
+                name: Name {
+                    span,
+                    text: Path::ERROR_NAME.to_string(),
+                },
+                arguments: vec![],
+            }],
+        }
+    }
+
+    fn pattern(&mut self, pattern: &patterns::Pattern<'i>) -> Pattern {
+        use nafi_ast::patterns::Pattern as P;
+        let span = self.span(pattern);
+        let kind = match pattern {
+            P::Wildcard(_) => PatternKind::Wildcard,
+            P::Tuple(tuple) => PatternKind::Tuple(
+                tuple
+                    .elements()
+                    .iter()
+                    .map(|element| self.pattern(element))
+                    .collect(),
+            ),
+            P::IntegerLiteral(literal) => {
+                PatternKind::Literal(Literal::Integer(literal.digits().to_string()))
+            }
+            P::BooleanLiteral(literal) => PatternKind::Literal(Literal::Boolean(literal.value())),
+            P::CharacterLiteral(literal) => PatternKind::Literal(self.character(literal)),
+            P::UnitLiteral(_) => PatternKind::Literal(Literal::Unit),
+            P::Record(record) => PatternKind::Record {
+                path: self.path(record.path()),
+                fields: record
+                    .fields()
+                    .iter()
+                    .map(|field| {
+                        let name = self.name(field.name());
+                        let pattern = match field.pattern() {
+                            Some(pattern) => self.pattern(pattern),
+                            None => Pattern {
+                                id: self.id(),
+                                span: name.span,
+                                kind: PatternKind::Binding(name.clone()),
+                            },
+                        };
+                        FieldPattern {
+                            span: self.span(field),
+                            name,
+                            pattern,
+                        }
+                    })
+                    .collect(),
+            },
+            P::Constructor(constructor) => PatternKind::Constructor {
+                path: self.path(constructor.path()),
+                fields: constructor
+                    .fields()
+                    .iter()
+                    .map(|field| self.pattern(field))
+                    .collect(),
+            },
+            P::Binding(identifier) => PatternKind::Binding(self.name(identifier)),
+        };
+        Pattern {
+            id: self.id(),
+            span,
+            kind,
+        }
+    }
+}
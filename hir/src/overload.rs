@@ -0,0 +1,450 @@
+//! Overload resolution, which selects the function declaration each call refers to.
+//!
+//! Function declarations of the same name in one scope overload each other,
+//! and a call of that name is matched against each of them:
+//!
+//! - Positional arguments bind the positional and labelled parameters in order,
+//!   and any left over are collected by a variadic parameter.
+//! - Named arguments bind the parameter with their label.
+//! - A trailing closure binds the last parameter.
+//! - Every parameter must be bound exactly once, unless it has a default value,
+//!   and each argument must have the type of the parameter it binds.
+//!
+//! If a call matches several overloads, those that leave the fewest parameters to their
+//! default values are preferred, and exactly one of them must remain.
+//! An argument whose type is not known is taken to have the type of any parameter.
+
+use crate::{
+    diagnostics::Diagnostic,
+    expressions::{Argument, Expr},
+    items::{Function, ParameterKind},
+    paths::Name,
+    types::Ty,
+    HirId, Span,
+};
+use std::collections::HashMap;
+
+/// The overload selected for a call, and how it binds the parameters.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Selection {
+    /// The id of the selected function declaration.
+    pub function: HirId,
+    /// How each parameter of the function is bound, in the order declared.
+    pub bindings: Vec<ParameterBinding>,
+}
+
+/// How a call binds a parameter.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ParameterBinding {
+    /// By the argument at the index in the call's arguments.
+    ///
+    /// The receiver of a method call is its first argument, before those in the parentheses.
+    Argument(usize),
+    /// By the list of the arguments at the indices, for a variadic parameter.
+    Variadic(Vec<usize>),
+    /// By the trailing closure.
+    TrailingClosure,
+    /// By the default value of the parameter.
+    Default,
+}
+
+/// The overload selected for each call of a function declaration in a program.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Overloads {
+    calls: HashMap<HirId, Selection>,
+}
+
+impl Overloads {
+    /// The overload selected for the call expression, if it calls a function declaration
+    /// and exactly one overload matches it.
+    pub fn get(&self, call: &Expr) -> Option<&Selection> {
+        self.calls.get(&call.id)
+    }
+
+    /// Record the overload selected for the call expression.
+    pub fn insert(&mut self, call: &Expr, selection: Selection) {
+        self.calls.insert(call.id, selection);
+    }
+}
+
+/// A function declaration, as calls see it.
+#[derive(Clone, Debug)]
+pub struct Signature<'h> {
+    /// The function declaration.
+    pub function: &'h Function,
+    /// The types of its parameters, in the order declared.
+    pub parameter_types: Vec<Ty>,
+}
+
+impl<'h> Signature<'h> {
+    /// The declaration as written, such as `f(x: Int, *, y: Bool = …)`.
+    pub fn describe(&self) -> String {
+        let mut parameters = vec![];
+        let mut keyword_only = false;
+        for parameter in &self.function.parameters {
+            match parameter.kind {
+                ParameterKind::Variadic => keyword_only = true,
+                ParameterKind::KeywordOnly if !keyword_only => {
+                    parameters.push("*".to_string());
+                    keyword_only = true;
+                }
+                _ => {}
+            }
+            let mut written = match &parameter.label {
+                Some(label) if parameter.kind == ParameterKind::Variadic => {
+                    format!("...{}: {}", label.text, parameter.ty)
+                }
+                Some(label) => format!("{}: {}", label.text, parameter.ty),
+                None => parameter.ty.to_string(),
+            };
+            if parameter.default.is_some() {
+                written.push_str(" = …");
+            }
+            parameters.push(written);
+        }
+        format!("{}({})", self.function.name.text, parameters.join(", "))
+    }
+
+    fn describe_parameter(&self, index: usize) -> String {
+        match &self.function.parameters[index].label {
+            Some(label) => format!("`{}`", label.text),
+            None => format!("parameter {}", index + 1),
+        }
+    }
+}
+
+/// A call, as overload resolution sees it.
+#[derive(Copy, Clone, Debug)]
+pub struct CallSite<'a> {
+    /// The span of the call.
+    pub span: Span,
+    /// The name of the called function, as written.
+    pub name: &'a str,
+    /// The arguments: the receiver of a method call, then those within the parentheses.
+    pub arguments: &'a [CallArgument<'a>],
+    /// The span and type of the trailing closure, if any.
+    pub trailing_closure: Option<(Span, &'a Ty)>,
+}
+
+/// An argument of a call, as overload resolution sees it.
+#[derive(Clone, Debug)]
+pub struct CallArgument<'a> {
+    /// The span of the argument, including its label.
+    pub span: Span,
+    /// The label of the argument, if it is named.
+    pub name: Option<&'a Name>,
+    /// The span of the value of the argument.
+    pub value_span: Span,
+    /// The type of the value, as far as it is known.
+    pub ty: Ty,
+}
+
+impl<'a> CallArgument<'a> {
+    /// An argument within the parentheses of a call.
+    pub fn new(argument: &'a Argument, ty: Ty) -> Self {
+        CallArgument {
+            span: argument.span,
+            name: argument.name.as_ref(),
+            value_span: argument.value.span,
+            ty,
+        }
+    }
+
+    /// The receiver of a method call, which is passed as its first positional argument.
+    pub fn receiver(receiver: &Expr, ty: Ty) -> Self {
+        CallArgument {
+            span: receiver.span,
+            name: None,
+            value_span: receiver.span,
+            ty,
+        }
+    }
+}
+
+/// Select the overload among the candidates that matches the call.
+///
+/// If none or several do, the error lists every candidate.
+pub fn select(candidates: &[Signature<'_>], call: &CallSite<'_>) -> Result<Selection, Diagnostic> {
+    let results: Vec<_> = candidates
+        .iter()
+        .map(|candidate| bind(candidate, call))
+        .collect();
+    let defaults = |index: usize| match &results[index] {
+        Ok(bindings) => Some(
+            bindings
+                .iter()
+                .filter(|&binding| *binding == ParameterBinding::Default)
+                .count(),
+        ),
+        Err(_) => None,
+    };
+    let fewest_defaults = (0..results.len()).filter_map(defaults).min();
+    let matching: Vec<_> = (0..results.len())
+        .filter(|&index| defaults(index).is_some() && defaults(index) == fewest_defaults)
+        .collect();
+    match (matching.as_slice(), results.as_slice()) {
+        (&[index], _) => Ok(Selection {
+            function: candidates[index].function.id,
+            bindings: results[index].clone().unwrap(),
+        }),
+        (&[], [Err(mismatch)]) => {
+            let candidate = &candidates[0];
+            Err(Diagnostic::error(
+                mismatch.span.unwrap_or(call.span),
+                mismatch.message(candidate),
+            )
+            .with_note_at(
+                candidate.function.name.span,
+                format!("`{}` is declared here", candidate.describe()),
+            ))
+        }
+        (&[], _) => {
+            let message = format!("no overload of `{}` matches these arguments", call.name);
+            let mut diagnostic = Diagnostic::error(call.span, message);
+            for (candidate, result) in candidates.iter().zip(&results) {
+                if let Err(mismatch) = result {
+                    diagnostic = diagnostic.with_note_at(
+                        candidate.function.name.span,
+                        format!(
+                            "`{}` does not match: {}",
+                            candidate.describe(),
+                            mismatch.message(candidate),
+                        ),
+                    );
+                }
+            }
+            Err(diagnostic)
+        }
+        (_, _) => {
+            let message = format!("this call of `{}` is ambiguous", call.name);
+            let mut diagnostic = Diagnostic::error(call.span, message);
+            for &index in &matching {
+                let candidate = &candidates[index];
+                diagnostic = diagnostic.with_note_at(
+                    candidate.function.name.span,
+                    format!("`{}` matches", candidate.describe()),
+                );
+            }
+            Err(diagnostic.with_note("add labels or type annotations to select one overload"))
+        }
+    }
+}
+
+/// Why a call does not match an overload.
+#[derive(Clone, Debug)]
+struct Mismatch {
+    span: Option<Span>,
+    kind: MismatchKind,
+}
+
+#[derive(Clone, Debug)]
+enum MismatchKind {
+    TooManyArguments {
+        expected: usize,
+        found: usize,
+    },
+    UnknownLabel(String),
+    NamedVariadic(usize),
+    BoundTwice(usize),
+    NoParameterForClosure,
+    VariadicClosure(usize),
+    Missing(usize),
+    Type {
+        parameter: usize,
+        expected: Ty,
+        found: Ty,
+    },
+}
+
+impl Mismatch {
+    fn message(&self, signature: &Signature<'_>) -> String {
+        match &self.kind {
+            MismatchKind::TooManyArguments { expected, found } => format!(
+                "expected at most {} positional argument{}, found {}",
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                found,
+            ),
+            MismatchKind::UnknownLabel(label) => {
+                let labels: Vec<_> = signature
+                    .function
+                    .parameters
+                    .iter()
+                    .filter_map(|parameter| parameter.label.as_ref())
+                    .map(|label| format!("`{}`", label.text))
+                    .collect();
+                match labels.as_slice() {
+                    [] => format!("there is no parameter labelled `{}`", label),
+                    _ => format!(
+                        "there is no parameter labelled `{}`, only {}",
+                        label,
+                        labels.join(", "),
+                    ),
+                }
+            }
+            MismatchKind::NamedVariadic(parameter) => format!(
+                "{} is variadic, and cannot be given by name",
+                signature.describe_parameter(*parameter),
+            ),
+            MismatchKind::BoundTwice(parameter) => format!(
+                "{} is given more than once",
+                signature.describe_parameter(*parameter),
+            ),
+            MismatchKind::NoParameterForClosure => {
+                "there is no parameter for the trailing closure".to_string()
+            }
+            MismatchKind::VariadicClosure(parameter) => format!(
+                "the trailing closure cannot be given to {}, which is variadic",
+                signature.describe_parameter(*parameter),
+            ),
+            MismatchKind::Missing(parameter) => format!(
+                "missing an argument for {}",
+                signature.describe_parameter(*parameter),
+            ),
+            MismatchKind::Type {
+                parameter,
+                expected,
+                found,
+            } => format!(
+                "{} expects a value of type `{}`, found `{}`",
+                signature.describe_parameter(*parameter),
+                expected,
+                found,
+            ),
+        }
+    }
+}
+
+/// Bind the arguments of the call to the parameters of the signature.
+fn bind(signature: &Signature<'_>, call: &CallSite<'_>) -> Result<Vec<ParameterBinding>, Mismatch> {
+    let parameters = &signature.function.parameters;
+    let mut bindings = vec![None; parameters.len()];
+    fn fail<T>(span: Option<Span>, kind: MismatchKind) -> Result<T, Mismatch> {
+        Err(Mismatch { span, kind })
+    }
+
+    let mut slots = parameters.iter().enumerate().filter(|(_, parameter)| {
+        parameter.kind == ParameterKind::Positional || parameter.kind == ParameterKind::Labelled
+    });
+    let mut slot_count = 0;
+    let mut left_over = vec![];
+    let positional = call
+        .arguments
+        .iter()
+        .enumerate()
+        .filter(|(_, argument)| argument.name.is_none());
+    for (index, _) in positional {
+        match slots.next() {
+            Some((parameter, _)) => {
+                bindings[parameter] = Some(ParameterBinding::Argument(index));
+                slot_count += 1;
+            }
+            None => left_over.push(index),
+        }
+    }
+    let variadic = parameters
+        .iter()
+        .position(|parameter| parameter.kind == ParameterKind::Variadic);
+    match variadic {
+        Some(parameter) => bindings[parameter] = Some(ParameterBinding::Variadic(left_over)),
+        None if !left_over.is_empty() => {
+            return fail(
+                Some(call.arguments[left_over[0]].span),
+                MismatchKind::TooManyArguments {
+                    expected: slot_count,
+                    found: slot_count + left_over.len(),
+                },
+            );
+        }
+        None => {}
+    }
+
+    for (index, argument) in call.arguments.iter().enumerate() {
+        let label = match &argument.name {
+            Some(label) => label,
+            None => continue,
+        };
+        let parameter = parameters.iter().position(|parameter| {
+            parameter.label.as_ref().map(|name| &name.text) == Some(&label.text)
+        });
+        let parameter = match parameter {
+            Some(parameter) => parameter,
+            None => {
+                let kind = MismatchKind::UnknownLabel(label.text.clone());
+                return fail(Some(label.span), kind);
+            }
+        };
+        if parameters[parameter].kind == ParameterKind::Variadic {
+            return fail(Some(label.span), MismatchKind::NamedVariadic(parameter));
+        }
+        if bindings[parameter].is_some() {
+            return fail(Some(argument.span), MismatchKind::BoundTwice(parameter));
+        }
+        bindings[parameter] = Some(ParameterBinding::Argument(index));
+    }
+
+    if let Some((span, _)) = call.trailing_closure {
+        let last = match parameters.len().checked_sub(1) {
+            Some(last) => last,
+            None => return fail(Some(span), MismatchKind::NoParameterForClosure),
+        };
+        if parameters[last].kind == ParameterKind::Variadic {
+            return fail(Some(span), MismatchKind::VariadicClosure(last));
+        }
+        if bindings[last].is_some() {
+            return fail(Some(span), MismatchKind::BoundTwice(last));
+        }
+        bindings[last] = Some(ParameterBinding::TrailingClosure);
+    }
+
+    let mut complete = vec![];
+    for (index, binding) in bindings.into_iter().enumerate() {
+        match binding {
+            Some(binding) => complete.push(binding),
+            None if parameters[index].default.is_some() => complete.push(ParameterBinding::Default),
+            None => return fail(None, MismatchKind::Missing(index)),
+        }
+    }
+
+    for (parameter, binding) in complete.iter().enumerate() {
+        let expected = &signature.parameter_types[parameter];
+        let check = |argument: usize, expected: &Ty| {
+            let found = &call.arguments[argument].ty;
+            if expected.accepts(found) {
+                Ok(())
+            } else {
+                let kind = MismatchKind::Type {
+                    parameter,
+                    expected: expected.clone(),
+                    found: found.clone(),
+                };
+                fail(Some(call.arguments[argument].value_span), kind)
+            }
+        };
+        match binding {
+            ParameterBinding::Argument(argument) => check(*argument, expected)?,
+            ParameterBinding::Variadic(arguments) => {
+                let element = match expected {
+                    Ty::List(element) => element,
+                    _ => continue,
+                };
+                for &argument in arguments {
+                    check(argument, element)?;
+                }
+            }
+            ParameterBinding::TrailingClosure => {
+                let (span, found) = call.trailing_closure.unwrap();
+                if !expected.accepts(found) {
+                    let kind = MismatchKind::Type {
+                        parameter,
+                        expected: expected.clone(),
+                        found: found.clone(),
+                    };
+                    return fail(Some(span), kind);
+                }
+            }
+            ParameterBinding::Default => {}
+        }
+    }
+    Ok(complete)
+}
@@ -0,0 +1,89 @@
+//! Names and paths, which refer to definitions.
+
+use crate::{HirId, Span};
+use std::fmt;
+
+/// A name, as written at the point of definition or use.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Name {
+    /// Where the name is written.
+    pub span: Span,
+    /// The text of the name.
+    pub text: String,
+}
+
+/// A path to a definition, such as `geometry::Shape::Circle`.
+///
+/// Paths are also used for types, which may be given arguments: `List[Int]`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Path {
+    /// Identifies the path, which name resolution resolves to a definition.
+    pub id: HirId,
+    /// The span of the whole path.
+    pub span: Span,
+    /// Whether this path is relative to the root module (`::a::b`) rather than the current scope.
+    pub global: bool,
+    /// The segments of the path, in order. There is always at least one.
+    pub segments: Vec<PathSegment>,
+}
+
+/// One segment of a path.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PathSegment {
+    /// The span of the segment, including its arguments.
+    pub span: Span,
+    /// The name of the segment.
+    pub name: Name,
+    /// The type arguments of this segment.
+    pub arguments: Vec<Path>,
+}
+
+impl Path {
+    /// The last segment of this path, which names the definition itself.
+    pub fn last(&self) -> &PathSegment {
+        self.segments.last().expect("path without a segment")
+    }
+
+    /// The name of [an erroneous path](Path::is_error), which cannot be written.
+    pub const ERROR_NAME: &'static str = "_";
+
+    /// Whether this path stands in for a type that could not be lowered, which was reported
+    /// then. It is not resolved, so it is of an unknown type and causes no further errors.
+    pub fn is_error(&self) -> bool {
+        match self.segments.as_slice() {
+            [segment] => segment.name.text == Path::ERROR_NAME,
+            _ => false,
+        }
+    }
+
+    /// The name this path refers to, if it is a single segment without type arguments.
+    pub fn as_name(&self) -> Option<&Name> {
+        match self.segments.as_slice() {
+            [segment] if !self.global && segment.arguments.is_empty() => Some(&segment.name),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Path {
+    /// Formats the path as it would be written, such as `::a::List[Int]`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, segment) in self.segments.iter().enumerate() {
+            if self.global || index > 0 {
+                f.write_str("::")?;
+            }
+            f.write_str(&segment.name.text)?;
+            if !segment.arguments.is_empty() {
+                f.write_str("[")?;
+                for (index, argument) in segment.arguments.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", argument)?;
+                }
+                f.write_str("]")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+//! Patterns, which match values and bind names to their parts.
+
+use crate::{
+    expressions::Literal,
+    paths::{Name, Path},
+    HirId, Span,
+};
+
+/// A pattern, matched against a value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pattern {
+    /// Identifies the pattern, and the binding it introduces if it is one.
+    pub id: HirId,
+    /// The span of the pattern.
+    pub span: Span,
+    /// What the pattern matches.
+    pub kind: PatternKind,
+}
+
+/// The kinds of pattern.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PatternKind {
+    /// Matches any value, without binding it: `_`.
+    Wildcard,
+    /// Matches any value, binding it to a name.
+    Binding(Name),
+    /// Matches a value equal to the literal.
+    Literal(Literal),
+    /// Matches each element of a tuple.
+    Tuple(Vec<Pattern>),
+    /// Matches a variant of an enum, and each of its fields in order.
+    Constructor {
+        /// The path naming the variant.
+        path: Path,
+        /// The patterns of the variant's fields.
+        fields: Vec<Pattern>,
+    },
+    /// Matches the named fields of a record.
+    Record {
+        /// The path naming the record type.
+        path: Path,
+        /// The patterns of the named fields.
+        fields: Vec<FieldPattern>,
+    },
+}
+
+/// Matches one field of a record.
+///
+/// The shorthand `Point { x }` is lowered to a binding pattern named after the field.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldPattern {
+    /// The span of the field pattern.
+    pub span: Span,
+    /// The name of the matched field.
+    pub name: Name,
+    /// The pattern matching the field's value.
+    pub pattern: Pattern,
+}
@@ -0,0 +1,23 @@
+//! The prelude, whose functions are in scope in every program.
+//!
+//! The prelude is written in Nafi, and every program is lowered with it as its
+//! [`prelude`](crate::items::Program::prelude) module.
+//! Its items are declared alongside the builtin types, outside of the root module,
+//! so any declaration of the same name shadows them.
+//!
+//! Function declarations of the prelude marked `#[native]` are built into the interpreter,
+//! which runs them by their name instead of their body.
+
+use crate::{items::Function, SourceId};
+
+/// The source of the prelude, whose spans are of [`SourceId::PRELUDE`].
+pub const SOURCE: &str = include_str!("prelude.nafi");
+
+/// Whether a function declaration is a native function of the prelude.
+pub fn is_native(function: &Function) -> bool {
+    function.span.source == SourceId::PRELUDE
+        && function
+            .attributes
+            .iter()
+            .any(|attribute| attribute.path.to_string() == "native")
+}
@@ -0,0 +1,749 @@
+//! Name resolution, which finds the definition each path refers to.
+//!
+//! Names are resolved lexically. Modules, blocks, function declarations, closures,
+//! match arms, and `for` loops each introduce a scope, and a name refers to its innermost
+//! definition in scope:
+//!
+//! - Items are in scope throughout the module or block that declares them, even before
+//!   their declaration. Function declarations of the same name in one scope are overloads
+//!   of each other; any other name defined twice in one scope is an error.
+//! - Bindings made by a pattern are in scope in the code the pattern guards: the body of a
+//!   closure or function, the guard and body of a match arm, or the body of a `for` loop.
+//!   A parameter of a function declaration is also in scope in the defaults of later parameters.
+//! - The local bindings and type parameters of a function are not in scope in items declared
+//!   inside of it.
+//! - The built-in types and the items of [the prelude](crate::prelude) are in scope everywhere,
+//!   unless shadowed. A declaration shadows every overload of a prelude function of its name.
+//!
+//! A path with more than one segment looks up each later segment in the module or enum before it.
+//! A global path (`::a::b`) starts from the root module instead of the current scope.
+//! The method of a method call `x.f(y)` is looked up as the function `f` of the call `f(x, y)`.
+//!
+//! A local binding or type parameter that shadows a name already in scope is reported as a warning,
+//! unless the name is a built-in or of the prelude.
+
+use crate::{
+    diagnostics::Diagnostic,
+    expressions::{Block, Expr, ExprKind, Stmt},
+    items::{Function, Import, Item, Module, Program},
+    paths::{Name, Path, PathSegment},
+    patterns::{Pattern, PatternKind},
+    HirId, SourceId, Span,
+};
+use std::collections::{hash_map::Entry, HashMap};
+
+/// What a path refers to.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Res {
+    /// A local binding, by the id of the pattern that binds it.
+    Local(HirId),
+    /// The overloads of a function, by the ids of their declarations, in the order declared.
+    Functions(Vec<HirId>),
+    /// A record type declaration.
+    Type(HirId),
+    /// An enum declaration.
+    Enum(HirId),
+    /// A variant of an enum.
+    Variant(HirId),
+    /// A module.
+    Module(HirId),
+    /// A generic type parameter of a function declaration.
+    Generic(HirId),
+    /// A built-in type.
+    Builtin(Builtin),
+    /// The path could not be resolved. An error has been reported for it.
+    Err,
+}
+
+/// The types built into the language, which are in scope everywhere.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Builtin {
+    /// Integers, `Int`.
+    Int,
+    /// Booleans, `Bool`.
+    Bool,
+    /// Unicode scalar values, `Char`.
+    Char,
+    /// Strings of text, `String`.
+    String,
+    /// The type of the unit value `()`, `Unit`.
+    Unit,
+    /// Lists of values of one type, `List[T]`.
+    List,
+    /// Tuples, by the types of their elements: `Tuple[Int, Bool]` is the type of `(1, true)`.
+    Tuple,
+    /// Functions, by the types of their parameters and then their return type:
+    /// `Function[Int, Int, Bool]` takes two `Int`s and returns a `Bool`.
+    Function,
+}
+
+impl Builtin {
+    /// Every built-in type.
+    pub const ALL: &'static [Builtin] = &[
+        Builtin::Int,
+        Builtin::Bool,
+        Builtin::Char,
+        Builtin::String,
+        Builtin::Unit,
+        Builtin::List,
+        Builtin::Tuple,
+        Builtin::Function,
+    ];
+
+    /// The name the type is in scope by.
+    pub fn name(self) -> &'static str {
+        match self {
+            Builtin::Int => "Int",
+            Builtin::Bool => "Bool",
+            Builtin::Char => "Char",
+            Builtin::String => "String",
+            Builtin::Unit => "Unit",
+            Builtin::List => "List",
+            Builtin::Tuple => "Tuple",
+            Builtin::Function => "Function",
+        }
+    }
+}
+
+/// The definition each path in a program refers to.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Resolutions {
+    paths: HashMap<HirId, Res>,
+    methods: HashMap<HirId, Res>,
+}
+
+impl Resolutions {
+    /// What the path refers to.
+    ///
+    /// This is `None` only for paths that are not resolved, which are those of attributes.
+    pub fn get(&self, path: &Path) -> Option<&Res> {
+        self.paths.get(&path.id)
+    }
+
+    /// What the method of the method call expression refers to.
+    ///
+    /// A method is looked up by its name in the scope of the call, like a function,
+    /// and the method call `x.f(y)` calls it as `f(x, y)`.
+    pub fn method(&self, call: &Expr) -> Option<&Res> {
+        self.methods.get(&call.id)
+    }
+
+    /// Record what a path created after name resolution refers to.
+    pub(crate) fn insert(&mut self, path: &Path, res: Res) {
+        self.paths.insert(path.id, res);
+    }
+}
+
+/// Resolve every path in the program.
+pub fn resolve(program: &Program) -> (Resolutions, Vec<Diagnostic>) {
+    let mut resolver = Resolver::default();
+    let prelude = resolver.scope(None, false);
+    for &builtin in Builtin::ALL {
+        resolver.scopes[prelude.0].names.insert(
+            builtin.name().to_string(),
+            Definition {
+                kind: DefKind::Builtin(builtin),
+                span: None,
+            },
+        );
+    }
+    // The items of the prelude module are declared beside the builtins, so that the root
+    // module can shadow them, rather than in a module of their own.
+    resolver.items(prelude, &program.prelude.items);
+    resolver.root = program.root.id;
+    let root = resolver.module(prelude, &program.root);
+    resolver.expr(root, &program.main);
+    resolver.finish()
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct ScopeId(usize);
+
+#[derive(Debug)]
+struct Scope {
+    parent: Option<ScopeId>,
+    /// Whether this scope is an item, whose own code cannot use the local bindings around it.
+    item: bool,
+    names: HashMap<String, Definition>,
+}
+
+#[derive(Clone, Debug)]
+struct Definition {
+    kind: DefKind,
+    /// The name at the definition, or `None` for built-ins.
+    span: Option<Span>,
+}
+
+#[derive(Clone, Debug)]
+enum DefKind {
+    Local(HirId),
+    Generic(HirId),
+    Functions(Vec<HirId>),
+    Type(HirId),
+    Enum(HirId),
+    Module(HirId),
+    Import(HirId),
+    Builtin(Builtin),
+}
+
+/// Resolution happens in two steps. First, the program is walked to build its scopes and
+/// collect every path along with the scope it is in. Then, once every scope is complete,
+/// each path is resolved, and imports are resolved as they are needed.
+#[derive(Default)]
+struct Resolver<'h> {
+    scopes: Vec<Scope>,
+    root: HirId,
+    module_scopes: HashMap<HirId, ScopeId>,
+    variants: HashMap<HirId, HashMap<String, HirId>>,
+    imports: Vec<(&'h Import, ScopeId)>,
+    /// The resolution of each import, or `None` while it is being resolved.
+    imported: HashMap<HirId, Option<Res>>,
+    paths: Vec<(&'h Path, ScopeId)>,
+    methods: Vec<(HirId, &'h Name, ScopeId)>,
+    resolutions: Resolutions,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'h> Resolver<'h> {
+    fn scope(&mut self, parent: Option<ScopeId>, item: bool) -> ScopeId {
+        self.scopes.push(Scope {
+            parent,
+            item,
+            names: HashMap::new(),
+        });
+        ScopeId(self.scopes.len() - 1)
+    }
+
+    fn error(&mut self, span: Span, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic::error(span, message));
+    }
+
+    // ~~~ building scopes ~~~ //
+
+    /// Define an item in the scope.
+    fn define(&mut self, scope: ScopeId, name: &Name, kind: DefKind) {
+        match self.scopes[scope.0].names.entry(name.text.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(Definition {
+                    kind,
+                    span: Some(name.span),
+                });
+            }
+            Entry::Occupied(mut entry) => match (&mut entry.get_mut().kind, kind) {
+                (DefKind::Functions(overloads), DefKind::Functions(more)) => overloads.extend(more),
+                _ => {
+                    let mut diagnostic = Diagnostic::error(
+                        name.span,
+                        format!("`{}` is defined multiple times", name.text),
+                    );
+                    if let Some(previous) = entry.get().span {
+                        diagnostic = diagnostic.with_note_at(
+                            previous,
+                            format!("previous definition of `{}` here", name.text),
+                        );
+                    }
+                    self.diagnostics.push(diagnostic);
+                }
+            },
+        }
+    }
+
+    /// Bind a local or type parameter in the scope.
+    ///
+    /// `bound` holds the names already bound by the same pattern or parameter list.
+    fn bind(
+        &mut self,
+        scope: ScopeId,
+        name: &Name,
+        kind: DefKind,
+        bound: &mut HashMap<String, Span>,
+    ) {
+        if let Some(&previous) = bound.get(&name.text) {
+            let diagnostic = Diagnostic::error(
+                name.span,
+                format!("`{}` is bound more than once", name.text),
+            )
+            .with_note_at(previous, format!("`{}` is first bound here", name.text));
+            self.diagnostics.push(diagnostic);
+            return;
+        }
+        bound.insert(name.text.clone(), name.span);
+        // Shadowing the prelude, like shadowing a builtin, is not worth a warning.
+        if let Some(shadowed) = self
+            .visible(scope, &name.text)
+            .and_then(|def| def.span)
+            .filter(|span| span.source != SourceId::PRELUDE)
+        {
+            let diagnostic = Diagnostic::warning(
+                name.span,
+                format!("`{}` shadows a definition that is in scope", name.text),
+            )
+            .with_note_at(shadowed, format!("`{}` is defined here", name.text));
+            self.diagnostics.push(diagnostic);
+        }
+        self.scopes[scope.0].names.insert(
+            name.text.clone(),
+            Definition {
+                kind,
+                span: Some(name.span),
+            },
+        );
+    }
+
+    /// The definition the name refers to in the scope, without resolving it.
+    fn visible(&self, scope: ScopeId, name: &str) -> Option<&Definition> {
+        let mut current = Some(scope);
+        let mut outside_item = false;
+        while let Some(id) = current {
+            let scope = &self.scopes[id.0];
+            match scope.names.get(name) {
+                Some(Definition {
+                    kind: DefKind::Local(_),
+                    ..
+                })
+                | Some(Definition {
+                    kind: DefKind::Generic(_),
+                    ..
+                }) if outside_item => {}
+                Some(definition) => return Some(definition),
+                None => {}
+            }
+            outside_item |= scope.item;
+            current = scope.parent;
+        }
+        None
+    }
+
+    fn module(&mut self, parent: ScopeId, module: &'h Module) -> ScopeId {
+        let scope = self.scope(Some(parent), true);
+        self.module_scopes.insert(module.id, scope);
+        self.items(scope, &module.items);
+        scope
+    }
+
+    fn items(&mut self, scope: ScopeId, items: &'h [Item]) {
+        for item in items {
+            self.declare(scope, item);
+        }
+        for item in items {
+            self.item(scope, item);
+        }
+    }
+
+    fn declare(&mut self, scope: ScopeId, item: &'h Item) {
+        match item {
+            Item::Function(function) => {
+                self.define(scope, &function.name, DefKind::Functions(vec![function.id]))
+            }
+            Item::Type(r#type) => self.define(scope, &r#type.name, DefKind::Type(r#type.id)),
+            Item::Enum(r#enum) => {
+                let mut variants = HashMap::new();
+                for variant in &r#enum.variants {
+                    if variants
+                        .insert(variant.name.text.clone(), variant.id)
+                        .is_some()
+                    {
+                        let message =
+                            format!("variant `{}` is defined multiple times", variant.name.text);
+                        self.error(variant.name.span, message);
+                    }
+                }
+                self.variants.insert(r#enum.id, variants);
+                self.define(scope, &r#enum.name, DefKind::Enum(r#enum.id))
+            }
+            Item::Module(module) => {
+                let name = module.name.as_ref().expect("nested module without a name");
+                self.define(scope, name, DefKind::Module(module.id))
+            }
+            Item::Import(import) => {
+                self.imports.push((import, scope));
+                self.define(scope, &import.name, DefKind::Import(import.id))
+            }
+        }
+    }
+
+    fn item(&mut self, scope: ScopeId, item: &'h Item) {
+        match item {
+            Item::Function(function) => self.function(scope, function),
+            Item::Type(r#type) => {
+                for field in &r#type.fields {
+                    self.path(scope, &field.ty);
+                }
+            }
+            Item::Enum(r#enum) => {
+                for field in r#enum.variants.iter().flat_map(|variant| &variant.fields) {
+                    self.path(scope, &field.ty);
+                }
+            }
+            Item::Module(module) => {
+                self.module(scope, module);
+            }
+            // Imports are resolved once every scope is built.
+            Item::Import(_) => {}
+        }
+    }
+
+    fn function(&mut self, scope: ScopeId, function: &'h Function) {
+        let mut scope = self.scope(Some(scope), true);
+        let mut bound = HashMap::new();
+        for generic in &function.generics {
+            self.bind(
+                scope,
+                &generic.name,
+                DefKind::Generic(generic.id),
+                &mut bound,
+            );
+        }
+        for parameter in &function.parameters {
+            self.path(scope, &parameter.ty);
+            if let Some(default) = &parameter.default {
+                self.expr(scope, default);
+            }
+            scope = self.scope(Some(scope), false);
+            self.pattern(scope, &parameter.pattern, &mut bound);
+        }
+        if let Some(return_type) = &function.return_type {
+            self.path(scope, return_type);
+        }
+        self.block(scope, &function.body);
+    }
+
+    fn block(&mut self, scope: ScopeId, block: &'h Block) {
+        let scope = self.scope(Some(scope), false);
+        for statement in &block.statements {
+            if let Stmt::Item(item) = statement {
+                self.declare(scope, item);
+            }
+        }
+        for statement in &block.statements {
+            match statement {
+                Stmt::Item(item) => self.item(scope, item),
+                Stmt::Expr(expr) => self.expr(scope, expr),
+            }
+        }
+        if let Some(tail) = &block.tail {
+            self.expr(scope, tail);
+        }
+    }
+
+    fn expr(&mut self, scope: ScopeId, expr: &'h Expr) {
+        match &expr.kind {
+            ExprKind::Literal(_) => {}
+            ExprKind::Path(path) => self.path(scope, path),
+            ExprKind::Tuple(elements) | ExprKind::List(elements) => {
+                for element in elements {
+                    self.expr(scope, element);
+                }
+            }
+            ExprKind::Record { path, fields } => {
+                self.path(scope, path);
+                for field in fields {
+                    self.expr(scope, &field.value);
+                }
+            }
+            ExprKind::Closure(closure) => {
+                let scope = self.scope(Some(scope), false);
+                let mut bound = HashMap::new();
+                for parameter in &closure.parameters {
+                    if let Some(ty) = &parameter.ty {
+                        self.path(scope, ty);
+                    }
+                    self.pattern(scope, &parameter.pattern, &mut bound);
+                }
+                self.block(scope, &closure.body);
+            }
+            ExprKind::Call {
+                callee,
+                arguments,
+                trailing_closure,
+            } => {
+                self.expr(scope, callee);
+                for argument in arguments {
+                    self.expr(scope, &argument.value);
+                }
+                if let Some(closure) = trailing_closure {
+                    self.expr(scope, closure);
+                }
+            }
+            ExprKind::MethodCall {
+                receiver,
+                method,
+                arguments,
+                trailing_closure,
+            } => {
+                self.methods.push((expr.id, method, scope));
+                self.expr(scope, receiver);
+                for argument in arguments {
+                    self.expr(scope, &argument.value);
+                }
+                if let Some(closure) = trailing_closure {
+                    self.expr(scope, closure);
+                }
+            }
+            ExprKind::Field { receiver, .. } => self.expr(scope, receiver),
+            ExprKind::Index { receiver, index } => {
+                self.expr(scope, receiver);
+                self.expr(scope, index);
+            }
+            ExprKind::Block(block) => self.block(scope, block),
+            ExprKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(scope, condition);
+                self.block(scope, then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.block(scope, else_branch);
+                }
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                self.expr(scope, scrutinee);
+                for arm in arms {
+                    let scope = self.scope(Some(scope), false);
+                    self.pattern(scope, &arm.pattern, &mut HashMap::new());
+                    if let Some(guard) = &arm.guard {
+                        self.expr(scope, guard);
+                    }
+                    self.expr(scope, &arm.body);
+                }
+            }
+            ExprKind::Loop { body, .. } => self.block(scope, body),
+            ExprKind::For {
+                pattern,
+                iterable,
+                body,
+                ..
+            } => {
+                self.expr(scope, iterable);
+                let scope = self.scope(Some(scope), false);
+                self.pattern(scope, pattern, &mut HashMap::new());
+                self.block(scope, body);
+            }
+            ExprKind::Break { value, .. } | ExprKind::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.expr(scope, value);
+                }
+            }
+            ExprKind::Continue { .. } => {}
+        }
+    }
+
+    fn pattern(&mut self, scope: ScopeId, pattern: &'h Pattern, bound: &mut HashMap<String, Span>) {
+        match &pattern.kind {
+            PatternKind::Wildcard | PatternKind::Literal(_) => {}
+            PatternKind::Binding(name) => self.bind(scope, name, DefKind::Local(pattern.id), bound),
+            PatternKind::Tuple(elements) => {
+                for element in elements {
+                    self.pattern(scope, element, bound);
+                }
+            }
+            PatternKind::Constructor { path, fields } => {
+                self.path(scope, path);
+                for field in fields {
+                    self.pattern(scope, field, bound);
+                }
+            }
+            PatternKind::Record { path, fields } => {
+                self.path(scope, path);
+                for field in fields {
+                    self.pattern(scope, &field.pattern, bound);
+                }
+            }
+        }
+    }
+
+    fn path(&mut self, scope: ScopeId, path: &'h Path) {
+        if path.is_error() {
+            return;
+        }
+        self.paths.push((path, scope));
+        for argument in path.segments.iter().flat_map(|segment| &segment.arguments) {
+            self.path(scope, argument);
+        }
+    }
+
+    // ~~~ resolving paths ~~~ //
+
+    fn finish(mut self) -> (Resolutions, Vec<Diagnostic>) {
+        for index in 0..self.imports.len() {
+            self.import(self.imports[index].0);
+        }
+        for (path, scope) in std::mem::take(&mut self.paths) {
+            let res = self.resolve_path(path, scope, None);
+            self.resolutions.paths.insert(path.id, res);
+        }
+        for (call, method, scope) in std::mem::take(&mut self.methods) {
+            let res = match self.lookup(scope, method, None) {
+                Some(res) => res,
+                None => {
+                    let message = format!("cannot find a function `{}` in this scope", method.text);
+                    self.diagnostics.push(
+                        Diagnostic::error(method.span, message).with_note(
+                            "a method call `x.f(y)` calls the function `f` as `f(x, y)`",
+                        ),
+                    );
+                    Res::Err
+                }
+            };
+            self.resolutions.methods.insert(call, res);
+        }
+        (self.resolutions, self.diagnostics)
+    }
+
+    fn import(&mut self, import: &'h Import) -> Res {
+        match self.imported.get(&import.id) {
+            Some(Some(res)) => return res.clone(),
+            Some(None) => {
+                let message = format!("cyclic import of `{}`", import.name.text);
+                self.error(import.path.span, message);
+                return Res::Err;
+            }
+            None => {}
+        }
+        self.imported.insert(import.id, None);
+        let scope = self
+            .imports
+            .iter()
+            .find(|(other, _)| other.id == import.id)
+            .expect("import without a scope")
+            .1;
+        let mut res = self.resolve_path(&import.path, scope, Some(import.id));
+        if let Res::Local(_) | Res::Generic(_) = res {
+            self.error(import.path.span, "only items can be imported");
+            res = Res::Err;
+        }
+        self.resolutions.paths.insert(import.path.id, res.clone());
+        self.imported.insert(import.id, Some(res.clone()));
+        res
+    }
+
+    /// Resolve the path in the scope.
+    ///
+    /// An import never refers to itself, so the import being resolved, if any, is skipped over.
+    fn resolve_path(&mut self, path: &Path, scope: ScopeId, importing: Option<HirId>) -> Res {
+        let (first, rest) = path.segments.split_first().expect("path without a segment");
+        let mut res = if path.global {
+            let root = Res::Module(self.root);
+            self.member(root, "the root module", first, importing)
+        } else {
+            match self.lookup(scope, &first.name, importing) {
+                Some(res) => res,
+                None => {
+                    let message = format!("cannot find `{}` in this scope", first.name.text);
+                    self.error(first.name.span, message);
+                    Res::Err
+                }
+            }
+        };
+        let mut owner = first;
+        for segment in rest {
+            let description = format!("`{}`", owner.name.text);
+            res = self.member(res, &description, segment, importing);
+            owner = segment;
+        }
+        res
+    }
+
+    fn lookup(&mut self, scope: ScopeId, name: &Name, importing: Option<HirId>) -> Option<Res> {
+        let mut current = Some(scope);
+        let mut outside_item = false;
+        while let Some(id) = current {
+            let scope = &self.scopes[id.0];
+            if let Some(definition) = scope.names.get(&name.text) {
+                match definition.kind {
+                    DefKind::Import(import) if Some(import) == importing => {}
+                    DefKind::Local(_) | DefKind::Generic(_) if outside_item => {
+                        let mut diagnostic = Diagnostic::error(
+                            name.span,
+                            format!("cannot use `{}` of an enclosing function here", name.text),
+                        )
+                        .with_note("declarations cannot capture the bindings around them");
+                        if let Some(span) = definition.span {
+                            diagnostic = diagnostic
+                                .with_note_at(span, format!("`{}` is defined here", name.text));
+                        }
+                        self.diagnostics.push(diagnostic);
+                        return Some(Res::Err);
+                    }
+                    ref kind => {
+                        let kind = kind.clone();
+                        return Some(self.res(kind));
+                    }
+                }
+            }
+            outside_item |= scope.item;
+            current = scope.parent;
+        }
+        None
+    }
+
+    /// Look up a later segment of a path in what the segments before it resolved to.
+    fn member(
+        &mut self,
+        owner: Res,
+        description: &str,
+        segment: &PathSegment,
+        importing: Option<HirId>,
+    ) -> Res {
+        let name = &segment.name;
+        match owner {
+            Res::Module(id) => {
+                let scope = self.module_scopes[&id];
+                let kind = match self.scopes[scope.0].names.get(&name.text) {
+                    Some(Definition {
+                        kind: DefKind::Import(import),
+                        ..
+                    }) if Some(*import) == importing => None,
+                    Some(definition) => Some(definition.kind.clone()),
+                    None => None,
+                };
+                match kind {
+                    Some(kind) => self.res(kind),
+                    None => {
+                        let message = format!("cannot find `{}` in {}", name.text, description);
+                        self.error(name.span, message);
+                        Res::Err
+                    }
+                }
+            }
+            Res::Enum(id) => match self.variants[&id].get(&name.text) {
+                Some(&variant) => Res::Variant(variant),
+                None => {
+                    let message = format!("no variant `{}` in {}", name.text, description);
+                    self.error(name.span, message);
+                    Res::Err
+                }
+            },
+            Res::Err => Res::Err,
+            _ => {
+                let message = format!(
+                    "cannot find `{}` in {}, which is not a module or enum",
+                    name.text, description,
+                );
+                self.error(name.span, message);
+                Res::Err
+            }
+        }
+    }
+
+    fn res(&mut self, kind: DefKind) -> Res {
+        match kind {
+            DefKind::Local(id) => Res::Local(id),
+            DefKind::Generic(id) => Res::Generic(id),
+            DefKind::Functions(overloads) => Res::Functions(overloads),
+            DefKind::Type(id) => Res::Type(id),
+            DefKind::Enum(id) => Res::Enum(id),
+            DefKind::Module(id) => Res::Module(id),
+            DefKind::Builtin(builtin) => Res::Builtin(builtin),
+            DefKind::Import(id) => {
+                let (import, _) = *self
+                    .imports
+                    .iter()
+                    .find(|(import, _)| import.id == id)
+                    .expect("undeclared import");
+                self.import(import)
+            }
+        }
+    }
+}
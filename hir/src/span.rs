@@ -0,0 +1,47 @@
+use std::fmt;
+
+/// Identifies a source file of a program.
+///
+/// The HIR does not own any source text; the driver keeps the file each id refers to,
+/// except for [the prelude](crate::prelude).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct SourceId(pub u32);
+
+impl SourceId {
+    /// Identifies the source of the prelude, [`prelude::SOURCE`](crate::prelude::SOURCE).
+    pub const PRELUDE: SourceId = SourceId(u32::MAX);
+}
+
+/// A region of a source file, by byte offsets.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Span {
+    /// The file this span is in.
+    pub source: SourceId,
+    /// The inclusive start byte index.
+    pub start: u32,
+    /// The exclusive end byte index.
+    pub end: u32,
+}
+
+impl Span {
+    /// Create a span of the given file.
+    pub fn new(source: SourceId, start: u32, end: u32) -> Self {
+        Span { source, start, end }
+    }
+
+    /// The smallest span covering both spans, which must be of the same file.
+    pub fn to(self, other: Span) -> Self {
+        debug_assert_eq!(self.source, other.source);
+        Span::new(
+            self.source,
+            self.start.min(other.start),
+            self.end.max(other.end),
+        )
+    }
+}
+
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}..{}", self.source.0, self.start, self.end)
+    }
+}
@@ -0,0 +1,201 @@
+//! The types of values.
+//!
+//! Types are written as paths, such as `Int`, `List[Point]`, or `Tuple[Int, Bool]`,
+//! and resolved to a [`Ty`] once the names in them are resolved.
+
+use crate::{
+    diagnostics::Diagnostic,
+    paths::Path,
+    resolve::{Builtin, Res, Resolutions},
+    HirId,
+};
+use std::fmt;
+
+/// A type.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Ty {
+    /// `Int`.
+    Int,
+    /// `Bool`.
+    Bool,
+    /// `Char`.
+    Char,
+    /// `String`.
+    String,
+    /// `Unit`, the type of `()`.
+    Unit,
+    /// `List[T]`.
+    List(Box<Ty>),
+    /// The type of a tuple, by the types of its elements.
+    Tuple(Vec<Ty>),
+    /// The type of a function, by the types of its parameters and its return type.
+    Function(Vec<Ty>, Box<Ty>),
+    /// A record type or enum, by the id of its declaration, and its name as written.
+    Named(HirId, String),
+    /// A generic type parameter, by the id of its declaration, and its name.
+    Generic(HirId, String),
+    /// A type yet to be inferred, by the number of its inference variable.
+    ///
+    /// These only exist while types are being checked, and never in its results.
+    Infer(u32),
+    /// A type that is not known.
+    ///
+    /// This is compatible with every type, so that an error is not reported again
+    /// wherever the erroneous value is used.
+    Unknown,
+}
+
+impl Ty {
+    /// The type a path written as a type refers to.
+    pub fn from_path(path: &Path, resolutions: &Resolutions) -> Result<Ty, Diagnostic> {
+        let name = &path.last().name.text;
+        let arguments = &path.last().arguments;
+        let arity = |expected: usize| {
+            if arguments.len() == expected {
+                Ok(())
+            } else {
+                let message = format!(
+                    "`{}` takes {} type argument{}, but {} were given",
+                    name,
+                    expected,
+                    if expected == 1 { "" } else { "s" },
+                    arguments.len(),
+                );
+                Err(Diagnostic::error(path.span, message))
+            }
+        };
+        let kind = match *resolutions.get(path).unwrap_or(&Res::Err) {
+            Res::Builtin(builtin) => {
+                match builtin {
+                    Builtin::List => {
+                        arity(1)?;
+                        let element = Ty::from_path(&arguments[0], resolutions)?;
+                        return Ok(Ty::List(Box::new(element)));
+                    }
+                    Builtin::Tuple => {
+                        if arguments.is_empty() {
+                            let message = "`Tuple` takes the types of its elements, \
+                                           but no type arguments were given";
+                            return Err(Diagnostic::error(path.span, message)
+                                .with_note("the type of the empty tuple `()` is `Unit`"));
+                        }
+                        let elements = arguments
+                            .iter()
+                            .map(|argument| Ty::from_path(argument, resolutions))
+                            .collect::<Result<_, _>>()?;
+                        return Ok(Ty::Tuple(elements));
+                    }
+                    Builtin::Function => {
+                        let mut types = arguments
+                            .iter()
+                            .map(|argument| Ty::from_path(argument, resolutions))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        let r#return = match types.pop() {
+                            Some(r#return) => r#return,
+                            None => {
+                                let message = "`Function` takes its parameter types and then its return type, \
+                                               but no type arguments were given";
+                                return Err(Diagnostic::error(path.span, message));
+                            }
+                        };
+                        return Ok(Ty::Function(types, Box::new(r#return)));
+                    }
+                    _ => {}
+                }
+                arity(0)?;
+                return Ok(match builtin {
+                    Builtin::Int => Ty::Int,
+                    Builtin::Bool => Ty::Bool,
+                    Builtin::Char => Ty::Char,
+                    Builtin::String => Ty::String,
+                    Builtin::Unit => Ty::Unit,
+                    Builtin::List | Builtin::Tuple | Builtin::Function => unreachable!(),
+                });
+            }
+            Res::Type(id) | Res::Enum(id) => {
+                arity(0)?;
+                return Ok(Ty::Named(id, name.clone()));
+            }
+            Res::Generic(id) => {
+                arity(0)?;
+                return Ok(Ty::Generic(id, name.clone()));
+            }
+            Res::Err => return Ok(Ty::Unknown),
+            Res::Local(_) => "local binding",
+            Res::Functions(_) => "function",
+            Res::Variant(_) => "enum variant",
+            Res::Module(_) => "module",
+        };
+        let message = format!("expected a type, found {} `{}`", kind, path);
+        Err(Diagnostic::error(path.span, message))
+    }
+
+    /// Whether the type is unknown, or made of one.
+    pub fn contains_unknown(&self) -> bool {
+        match self {
+            Ty::Unknown => true,
+            Ty::List(element) => element.contains_unknown(),
+            Ty::Tuple(elements) => elements.iter().any(Ty::contains_unknown),
+            Ty::Function(parameters, r#return) => {
+                parameters.iter().any(Ty::contains_unknown) || r#return.contains_unknown()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a value of type `other` may be used where this type is expected.
+    ///
+    /// Unknown types, types yet to be inferred, and generic type parameters
+    /// are compatible with every type.
+    pub fn accepts(&self, other: &Ty) -> bool {
+        match (self, other) {
+            (Ty::Unknown, _) | (_, Ty::Unknown) | (Ty::Infer(_), _) | (_, Ty::Infer(_)) => true,
+            (Ty::Generic(..), _) => true,
+            (Ty::List(expected), Ty::List(found)) => expected.accepts(found),
+            (Ty::Tuple(expected), Ty::Tuple(found)) => {
+                expected.len() == found.len()
+                    && expected.iter().zip(found).all(|(e, f)| e.accepts(f))
+            }
+            (Ty::Function(expected, expected_return), Ty::Function(found, found_return)) => {
+                expected.len() == found.len()
+                    && expected.iter().zip(found).all(|(e, f)| f.accepts(e))
+                    && expected_return.accepts(found_return)
+            }
+            (Ty::Named(expected, _), Ty::Named(found, _)) => expected == found,
+            (expected, found) => expected == found,
+        }
+    }
+}
+
+impl fmt::Display for Ty {
+    /// Formats the type as it would be written.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ty::Int => f.write_str("Int"),
+            Ty::Bool => f.write_str("Bool"),
+            Ty::Char => f.write_str("Char"),
+            Ty::String => f.write_str("String"),
+            Ty::Unit => f.write_str("Unit"),
+            Ty::List(element) => write!(f, "List[{}]", element),
+            Ty::Tuple(elements) => {
+                f.write_str("Tuple[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                f.write_str("]")
+            }
+            Ty::Function(parameters, r#return) => {
+                f.write_str("Function[")?;
+                for parameter in parameters {
+                    write!(f, "{}, ", parameter)?;
+                }
+                write!(f, "{}]", r#return)
+            }
+            Ty::Named(_, name) | Ty::Generic(_, name) => f.write_str(name),
+            Ty::Infer(_) | Ty::Unknown => f.write_str("_"),
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Traversal of the HIR.
+//!
+//! A [`Visitor`] is called for each node of a tree. Each method defaults to visiting the
+//! children of the node, through the matching `walk_` function; an implementation that
+//! overrides a method calls the `walk_` function itself to continue into the children.
+//! A [`VisitorMut`] does the same with mutable access, for passes that rewrite the HIR.
+
+use crate::{
+    expressions::{Block, Expr, ExprKind, Stmt},
+    items::{Function, Item, Module, Program},
+    paths::Path,
+    patterns::{Pattern, PatternKind},
+};
+
+/// Visits the nodes of the HIR. See the [module documentation](self).
+pub trait Visitor<'h> {
+    /// Visit a module.
+    fn visit_module(&mut self, module: &'h Module) {
+        walk_module(self, module)
+    }
+
+    /// Visit an item.
+    fn visit_item(&mut self, item: &'h Item) {
+        walk_item(self, item)
+    }
+
+    /// Visit a function declaration.
+    fn visit_function(&mut self, function: &'h Function) {
+        walk_function(self, function)
+    }
+
+    /// Visit a block.
+    fn visit_block(&mut self, block: &'h Block) {
+        walk_block(self, block)
+    }
+
+    /// Visit an expression.
+    fn visit_expr(&mut self, expr: &'h Expr) {
+        walk_expr(self, expr)
+    }
+
+    /// Visit a pattern.
+    fn visit_pattern(&mut self, pattern: &'h Pattern) {
+        walk_pattern(self, pattern)
+    }
+
+    /// Visit a path, used as a type or to refer to a definition.
+    fn visit_path(&mut self, path: &'h Path) {
+        walk_path(self, path)
+    }
+}
+
+/// Visit the prelude and the root module of the program, then its main function.
+pub fn walk_program<'h, V: Visitor<'h> + ?Sized>(visitor: &mut V, program: &'h Program) {
+    visitor.visit_module(&program.prelude);
+    visitor.visit_module(&program.root);
+    visitor.visit_expr(&program.main);
+}
+
+/// Visit the items of the module.
+pub fn walk_module<'h, V: Visitor<'h> + ?Sized>(visitor: &mut V, module: &'h Module) {
+    for item in &module.items {
+        visitor.visit_item(item);
+    }
+}
+
+/// Visit the contents of the item.
+pub fn walk_item<'h, V: Visitor<'h> + ?Sized>(visitor: &mut V, item: &'h Item) {
+    match item {
+        Item::Function(function) => visitor.visit_function(function),
+        Item::Type(r#type) => {
+            for field in &r#type.fields {
+                visitor.visit_path(&field.ty);
+            }
+        }
+        Item::Enum(r#enum) => {
+            for field in r#enum.variants.iter().flat_map(|variant| &variant.fields) {
+                visitor.visit_path(&field.ty);
+            }
+        }
+        Item::Module(module) => visitor.visit_module(module),
+        Item::Import(import) => visitor.visit_path(&import.path),
+    }
+}
+
+/// Visit the parameters, return type, and body of the function.
+pub fn walk_function<'h, V: Visitor<'h> + ?Sized>(visitor: &mut V, function: &'h Function) {
+    for parameter in &function.parameters {
+        visitor.visit_path(&parameter.ty);
+        if let Some(default) = &parameter.default {
+            visitor.visit_expr(default);
+        }
+        visitor.visit_pattern(&parameter.pattern);
+    }
+    if let Some(return_type) = &function.return_type {
+        visitor.visit_path(return_type);
+    }
+    visitor.visit_block(&function.body);
+}
+
+/// Visit the statements of the block, then its tail expression.
+pub fn walk_block<'h, V: Visitor<'h> + ?Sized>(visitor: &mut V, block: &'h Block) {
+    for statement in &block.statements {
+        match statement {
+            Stmt::Item(item) => visitor.visit_item(item),
+            Stmt::Expr(expr) => visitor.visit_expr(expr),
+        }
+    }
+    if let Some(tail) = &block.tail {
+        visitor.visit_expr(tail);
+    }
+}
+
+/// Visit the children of the expression, in evaluation order.
+pub fn walk_expr<'h, V: Visitor<'h> + ?Sized>(visitor: &mut V, expr: &'h Expr) {
+    match &expr.kind {
+        ExprKind::Literal(_) | ExprKind::Continue { .. } => {}
+        ExprKind::Path(path) => visitor.visit_path(path),
+        ExprKind::Tuple(elements) | ExprKind::List(elements) => {
+            for element in elements {
+                visitor.visit_expr(element);
+            }
+        }
+        ExprKind::Record { path, fields } => {
+            visitor.visit_path(path);
+            for field in fields {
+                visitor.visit_expr(&field.value);
+            }
+        }
+        ExprKind::Closure(closure) => {
+            for parameter in &closure.parameters {
+                if let Some(ty) = &parameter.ty {
+                    visitor.visit_path(ty);
+                }
+                visitor.visit_pattern(&parameter.pattern);
+            }
+            visitor.visit_block(&closure.body);
+        }
+        ExprKind::Call {
+            callee,
+            arguments,
+            trailing_closure,
+        } => {
+            visitor.visit_expr(callee);
+            for argument in arguments {
+                visitor.visit_expr(&argument.value);
+            }
+            if let Some(closure) = trailing_closure {
+                visitor.visit_expr(closure);
+            }
+        }
+        ExprKind::MethodCall {
+            receiver,
+            arguments,
+            trailing_closure,
+            ..
+        } => {
+            visitor.visit_expr(receiver);
+            for argument in arguments {
+                visitor.visit_expr(&argument.value);
+            }
+            if let Some(closure) = trailing_closure {
+                visitor.visit_expr(closure);
+            }
+        }
+        ExprKind::Field { receiver, .. } => visitor.visit_expr(receiver),
+        ExprKind::Index { receiver, index } => {
+            visitor.visit_expr(receiver);
+            visitor.visit_expr(index);
+        }
+        ExprKind::Block(block) => visitor.visit_block(block),
+        ExprKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            visitor.visit_expr(condition);
+            visitor.visit_block(then_branch);
+            if let Some(else_branch) = else_branch {
+                visitor.visit_block(else_branch);
+            }
+        }
+        ExprKind::Match { scrutinee, arms } => {
+            visitor.visit_expr(scrutinee);
+            for arm in arms {
+                visitor.visit_pattern(&arm.pattern);
+                if let Some(guard) = &arm.guard {
+                    visitor.visit_expr(guard);
+                }
+                visitor.visit_expr(&arm.body);
+            }
+        }
+        ExprKind::Loop { body, .. } => visitor.visit_block(body),
+        ExprKind::For {
+            pattern,
+            iterable,
+            body,
+            ..
+        } => {
+            visitor.visit_expr(iterable);
+            visitor.visit_pattern(pattern);
+            visitor.visit_block(body);
+        }
+        ExprKind::Break { value, .. } | ExprKind::Return { value, .. } => {
+            if let Some(value) = value {
+                visitor.visit_expr(value);
+            }
+        }
+    }
+}
+
+/// Visit the subpatterns of the pattern, and the paths it names.
+pub fn walk_pattern<'h, V: Visitor<'h> + ?Sized>(visitor: &mut V, pattern: &'h Pattern) {
+    match &pattern.kind {
+        PatternKind::Wildcard | PatternKind::Binding(_) | PatternKind::Literal(_) => {}
+        PatternKind::Tuple(elements) => {
+            for element in elements {
+                visitor.visit_pattern(element);
+            }
+        }
+        PatternKind::Constructor { path, fields } => {
+            visitor.visit_path(path);
+            for field in fields {
+                visitor.visit_pattern(field);
+            }
+        }
+        PatternKind::Record { path, fields } => {
+            visitor.visit_path(path);
+            for field in fields {
+                visitor.visit_pattern(&field.pattern);
+            }
+        }
+    }
+}
+
+/// Visit the type arguments of the path.
+pub fn walk_path<'h, V: Visitor<'h> + ?Sized>(visitor: &mut V, path: &'h Path) {
+    for argument in path.segments.iter().flat_map(|segment| &segment.arguments) {
+        visitor.visit_path(argument);
+    }
+}
+
+/// Visits the nodes of the HIR mutably, for passes that rewrite it.
+///
+/// This is like [`Visitor`], but patterns and paths are not visited, since they contain
+/// no expressions.
+pub trait VisitorMut {
+    /// Visit a module.
+    fn visit_module(&mut self, module: &mut Module) {
+        walk_module_mut(self, module)
+    }
+
+    /// Visit an item.
+    fn visit_item(&mut self, item: &mut Item) {
+        walk_item_mut(self, item)
+    }
+
+    /// Visit a function declaration.
+    fn visit_function(&mut self, function: &mut Function) {
+        walk_function_mut(self, function)
+    }
+
+    /// Visit a block.
+    fn visit_block(&mut self, block: &mut Block) {
+        walk_block_mut(self, block)
+    }
+
+    /// Visit an expression.
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr)
+    }
+}
+
+/// Visit the prelude and the root module of the program mutably, then its main function.
+pub fn walk_program_mut<V: VisitorMut + ?Sized>(visitor: &mut V, program: &mut Program) {
+    visitor.visit_module(&mut program.prelude);
+    visitor.visit_module(&mut program.root);
+    visitor.visit_expr(&mut program.main);
+}
+
+/// Visit the items of the module mutably.
+pub fn walk_module_mut<V: VisitorMut + ?Sized>(visitor: &mut V, module: &mut Module) {
+    for item in &mut module.items {
+        visitor.visit_item(item);
+    }
+}
+
+/// Visit the function declarations and modules an item contains mutably.
+pub fn walk_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut Item) {
+    match item {
+        Item::Function(function) => visitor.visit_function(function),
+        Item::Module(module) => visitor.visit_module(module),
+        Item::Type(_) | Item::Enum(_) | Item::Import(_) => {}
+    }
+}
+
+/// Visit the parameter defaults and the body of the function mutably.
+pub fn walk_function_mut<V: VisitorMut + ?Sized>(visitor: &mut V, function: &mut Function) {
+    for parameter in &mut function.parameters {
+        if let Some(default) = &mut parameter.default {
+            visitor.visit_expr(default);
+        }
+    }
+    visitor.visit_block(&mut function.body);
+}
+
+/// Visit the statements of the block mutably, then its tail expression.
+pub fn walk_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, block: &mut Block) {
+    for statement in &mut block.statements {
+        match statement {
+            Stmt::Item(item) => visitor.visit_item(item),
+            Stmt::Expr(expr) => visitor.visit_expr(expr),
+        }
+    }
+    if let Some(tail) = &mut block.tail {
+        visitor.visit_expr(tail);
+    }
+}
+
+/// Visit the child expressions and blocks of the expression mutably, in evaluation order.
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match &mut expr.kind {
+        ExprKind::Literal(_) | ExprKind::Path(_) | ExprKind::Continue { .. } => {}
+        ExprKind::Tuple(elements) | ExprKind::List(elements) => {
+            for element in elements {
+                visitor.visit_expr(element);
+            }
+        }
+        ExprKind::Record { fields, .. } => {
+            for field in fields {
+                visitor.visit_expr(&mut field.value);
+            }
+        }
+        ExprKind::Closure(closure) => visitor.visit_block(&mut closure.body),
+        ExprKind::Call {
+            callee,
+            arguments,
+            trailing_closure,
+        } => {
+            visitor.visit_expr(callee);
+            for argument in arguments {
+                visitor.visit_expr(&mut argument.value);
+            }
+            if let Some(closure) = trailing_closure {
+                visitor.visit_expr(closure);
+            }
+        }
+        ExprKind::MethodCall {
+            receiver,
+            arguments,
+            trailing_closure,
+            ..
+        } => {
+            visitor.visit_expr(receiver);
+            for argument in arguments {
+                visitor.visit_expr(&mut argument.value);
+            }
+            if let Some(closure) = trailing_closure {
+                visitor.visit_expr(closure);
+            }
+        }
+        ExprKind::Field { receiver, .. } => visitor.visit_expr(receiver),
+        ExprKind::Index { receiver, index } => {
+            visitor.visit_expr(receiver);
+            visitor.visit_expr(index);
+        }
+        ExprKind::Block(block) => visitor.visit_block(block),
+        ExprKind::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            visitor.visit_expr(condition);
+            visitor.visit_block(then_branch);
+            if let Some(else_branch) = else_branch {
+                visitor.visit_block(else_branch);
+            }
+        }
+        ExprKind::Match { scrutinee, arms } => {
+            visitor.visit_expr(scrutinee);
+            for arm in arms {
+                if let Some(guard) = &mut arm.guard {
+                    visitor.visit_expr(guard);
+                }
+                visitor.visit_expr(&mut arm.body);
+            }
+        }
+        ExprKind::Loop { body, .. } => visitor.visit_block(body),
+        ExprKind::For { iterable, body, .. } => {
+            visitor.visit_expr(iterable);
+            visitor.visit_block(body);
+        }
+        ExprKind::Break { value, .. } | ExprKind::Return { value, .. } => {
+            if let Some(value) = value {
+                visitor.visit_expr(value);
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Checks that calls are desugared into calls with positional arguments alone.
+
+use nafi_hir::{
+    expressions::{Expr, ExprKind},
+    lower, SourceId, Span,
+};
+
+/// The source text of the span.
+fn text(source: &str, span: Span) -> &str {
+    &source[span.start as usize..span.end as usize]
+}
+
+/// An argument of a desugared call, as its source text.
+///
+/// The list a variadic parameter collects is shown as the list of its elements.
+fn show(source: &str, expr: &Expr) -> String {
+    match &expr.kind {
+        ExprKind::List(elements) => {
+            let elements: Vec<_> = elements
+                .iter()
+                .map(|element| show(source, element))
+                .collect();
+            format!("[{}]", elements.join(", "))
+        }
+        _ => text(source, expr.span).to_string(),
+    }
+}
+
+/// The arguments of the call that gives the value of a script, once it is desugared.
+fn arguments(source: &str) -> Vec<String> {
+    let function = nafi_parser::parse(source).expect("the script parses");
+    let (program, diagnostics) = lower::lower_script(SourceId(0), &function);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    let (analysis, diagnostics) = nafi_hir::analyze(program);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    let call = match &analysis.program.main.kind {
+        ExprKind::Closure(closure) => closure.body.tail.as_ref().expect("the script has a value"),
+        _ => unreachable!("the main function is a closure"),
+    };
+    match &call.kind {
+        ExprKind::Call {
+            arguments,
+            trailing_closure: None,
+            ..
+        } => arguments
+            .iter()
+            .map(|argument| {
+                assert!(argument.name.is_none(), "{:?}", argument.name);
+                show(source, &argument.value)
+            })
+            .collect(),
+        kind => panic!(
+            "expected a call with positional arguments alone, found {:?}",
+            kind
+        ),
+    }
+}
+
+/// The spans and messages of the diagnostics of a script, in order.
+fn diagnostics(source: &str) -> Vec<(&str, String)> {
+    let function = nafi_parser::parse(source).expect("the script parses");
+    let (program, mut diagnostics) = lower::lower_script(SourceId(0), &function);
+    diagnostics.extend(nafi_hir::analyze(program).1);
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| (text(source, diagnostic.span), diagnostic.message))
+        .collect()
+}
+
+#[test]
+fn labelled_arguments_move_to_the_position_of_their_parameter() {
+    let source = "{
+        function range(start: Int, end: Int, step: Int = 1) = { start }
+        range(0, step = 2, end = 10)
+    }";
+    assert_eq!(arguments(source), ["0", "10", "2"]);
+}
+
+#[test]
+fn trailing_closures_become_the_argument_of_the_last_parameter() {
+    let source = "{
+        function each(xs: List[Int], *, reversed: Bool = false, body: Function[Int, Unit]) = { }
+        each(reversed = true, xs = [1, 2]) { x -> print(x) }
+    }";
+    assert_eq!(arguments(source), ["[1, 2]", "true", "{ x -> print(x) }"],);
+}
+
+#[test]
+fn method_receivers_become_the_first_argument() {
+    let source = "{
+        function scale(factor: Int, value: Int) = { mul(factor, value) }
+        2.scale(value = 3)
+    }";
+    assert_eq!(arguments(source), ["2", "3"]);
+}
+
+#[test]
+fn variadic_arguments_are_gathered_into_a_list() {
+    let source = "{
+        function sum(...values: List[Int], *, start: Int = 0) = { start }
+        sum(1, 2, 3, start = 5)
+    }";
+    assert_eq!(arguments(source), ["[1, 2, 3]", "5"]);
+    let source = "{
+        function sum(...values: List[Int]) = { 0 }
+        sum()
+    }";
+    assert_eq!(arguments(source), ["[]"]);
+}
+
+#[test]
+fn labels_given_twice_are_reported_once() {
+    let source = "{
+        function range(start: Int, end: Int) = { start }
+        range(start = 1, end = 2, start = 3)
+    }";
+    assert_eq!(
+        diagnostics(source),
+        [(
+            "start",
+            "the argument `start` is given more than once".to_string(),
+        )],
+    );
+}
+
+#[test]
+fn labels_no_parameter_has_are_reported() {
+    let source = "{
+        function range(start: Int, end: Int) = { start }
+        range(0, stop = 10)
+    }";
+    assert_eq!(
+        diagnostics(source),
+        [(
+            "stop",
+            "there is no parameter labelled `stop`, only `start`, `end`".to_string(),
+        )],
+    );
+}
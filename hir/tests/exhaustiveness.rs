@@ -0,0 +1,93 @@
+//! Checks that `match`es cover every value, and that their arms can be reached.
+
+use nafi_hir::{lower, SourceId};
+
+/// The messages of the diagnostics of a script, in order.
+fn diagnostics(source: &str) -> Vec<String> {
+    let function = nafi_parser::parse(source).expect("the script parses");
+    let (program, mut diagnostics) = lower::lower_script(SourceId(0), &function);
+    diagnostics.extend(nafi_hir::analyze(program).1);
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect()
+}
+
+const SHAPE: &str = "enum Shape { Circle(r: Int), Square(side: Int), Empty }";
+
+#[test]
+fn matches_covering_every_value_pass() {
+    let source = format!(
+        "{{
+            {}
+            function area(shape: Shape): Int = {{
+                match shape {{
+                    Shape::Circle(r) => mul(3, mul(r, r)),
+                    Shape::Square {{ side }} => mul(side, side),
+                    Shape::Empty => 0,
+                }}
+            }}
+            match (true, area(Shape::Empty)) {{
+                (true, 0) => 1,
+                (false, _) => 2,
+                (_, n) if lt(n, 0) => 3,
+                (true, _) => 4,
+            }}
+        }}",
+        SHAPE,
+    );
+    assert_eq!(diagnostics(&source), Vec::<String>::new());
+}
+
+#[test]
+fn literals_without_a_wildcard_are_not_exhaustive() {
+    assert_eq!(
+        diagnostics("{ match 3 { 1 => 1 } }"),
+        ["this `match` does not cover every value: `_` is not matched"],
+    );
+}
+
+#[test]
+fn missing_variants_are_not_exhaustive() {
+    let source = format!(
+        "{{ {} match Shape::Empty {{ Shape::Circle(r) => r }} }}",
+        SHAPE,
+    );
+    assert_eq!(
+        diagnostics(&source),
+        ["this `match` does not cover every value: `Shape::Square(_)` is not matched"],
+    );
+}
+
+#[test]
+fn missing_tuples_are_not_exhaustive() {
+    assert_eq!(
+        diagnostics("{ match (true, false) { (true, _) => 1, (_, true) => 2 } }"),
+        ["this `match` does not cover every value: `(false, false)` is not matched"],
+    );
+}
+
+#[test]
+fn guarded_arms_do_not_cover_values() {
+    assert_eq!(
+        diagnostics("{ match true { true => 1, false if true => 2 } }"),
+        ["this `match` does not cover every value: `false` is not matched"],
+    );
+}
+
+#[test]
+fn arms_after_covering_arms_are_unreachable() {
+    let unreachable = "unreachable arm: every value it matches is matched by an earlier arm";
+    assert_eq!(
+        diagnostics("{ match true { true => 1, false => 2, _ => 3 } }"),
+        [unreachable],
+    );
+    assert_eq!(
+        diagnostics("{ match 7 { 7 => 1, 07 => 2, _ => 3 } }"),
+        [unreachable],
+    );
+    assert_eq!(
+        diagnostics("{ match (1, 'a') { (_, 'a') => 1, (n, c) => 2, (1, 'b') => 3 } }"),
+        [unreachable],
+    );
+}
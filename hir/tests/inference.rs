@@ -0,0 +1,53 @@
+//! Checks that the types of declarations are inferred where they are not written.
+
+use nafi_hir::{lower, SourceId};
+
+/// The messages of the diagnostics of a script, in order.
+fn diagnostics(source: &str) -> Vec<String> {
+    let function = nafi_parser::parse(source).expect("the script parses");
+    let (program, mut diagnostics) = lower::lower_script(SourceId(0), &function);
+    diagnostics.extend(nafi_hir::analyze(program).1);
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect()
+}
+
+#[test]
+fn inferred_return_types_of_generic_functions_are_instantiated() {
+    assert_eq!(
+        diagnostics("{ function id[T](x: T) = { x } add(id(1), 2) }"),
+        Vec::<String>::new(),
+    );
+    assert_eq!(
+        diagnostics(
+            "{
+                function pair[T](x: T) = { (x, x) }
+                match (pair(1), pair(true)) { ((a, _), (true, _)) => a, _ => 0 }
+            }"
+        ),
+        Vec::<String>::new(),
+    );
+}
+
+#[test]
+fn return_types_are_inferred_before_earlier_calls_are_checked() {
+    assert_eq!(
+        diagnostics(
+            "{
+                function double() = { mul(first([3]), 2) }
+                function first[T](xs: List[T]) = { xs[0] }
+                double()
+            }"
+        ),
+        Vec::<String>::new(),
+    );
+}
+
+#[test]
+fn inferred_return_types_are_still_checked() {
+    assert_eq!(
+        diagnostics("{ function id[T](x: T) = { x } not(id(1)) }"),
+        ["`condition` expects a value of type `Bool`, found `Int`"],
+    );
+}
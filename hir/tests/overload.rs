@@ -0,0 +1,169 @@
+//! Checks which overload each call selects, and the diagnostics of calls that select none.
+
+use nafi_hir::{
+    check, expressions::Expr, items::Function, lower, overload::Signature, resolve, visit,
+    visit::Visitor, HirId, SourceId,
+};
+use std::collections::HashMap;
+
+/// Collects the function declarations and the expressions it visits.
+#[derive(Default)]
+struct Nodes<'h> {
+    functions: HashMap<HirId, &'h Function>,
+    calls: Vec<&'h Expr>,
+}
+
+impl<'h> Visitor<'h> for Nodes<'h> {
+    fn visit_function(&mut self, function: &'h Function) {
+        self.functions.insert(function.id, function);
+        visit::walk_function(self, function);
+    }
+
+    fn visit_expr(&mut self, expr: &'h Expr) {
+        self.calls.push(expr);
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// The declarations the calls in the statements of a script select, as written,
+/// in the order the calls begin.
+fn selected(source: &str) -> Vec<String> {
+    let function = nafi_parser::parse(source).expect("the script parses");
+    let (program, diagnostics) = lower::lower_script(SourceId(0), &function);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    let (resolutions, diagnostics) = resolve::resolve(&program);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    let (typing, diagnostics) = check::check(&program, &resolutions);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+    let mut declarations = Nodes::default();
+    visit::walk_program(&mut declarations, &program);
+    let mut calls = Nodes::default();
+    calls.visit_expr(&program.main);
+    calls
+        .calls
+        .into_iter()
+        .filter_map(|call| typing.overloads().get(call))
+        .map(|selection| {
+            let signature = Signature {
+                function: declarations.functions[&selection.function],
+                parameter_types: vec![],
+            };
+            signature.describe()
+        })
+        .collect()
+}
+
+/// The message of the only diagnostic of a script, and the messages of its notes.
+fn error(source: &str) -> (String, Vec<String>) {
+    let function = nafi_parser::parse(source).expect("the script parses");
+    let (program, mut diagnostics) = lower::lower_script(SourceId(0), &function);
+    diagnostics.extend(nafi_hir::analyze(program).1);
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    let diagnostic = diagnostics.remove(0);
+    let notes = diagnostic.notes.into_iter().map(|note| note.message);
+    (diagnostic.message, notes.collect())
+}
+
+const AREA: &str = "
+    function area(side: Int): Int = { side }
+    function area(width: Int, height: Int): Int = { width }
+    function area(*, radius: Int): Int = { radius }
+    function area(inside: Bool): Int = { 0 }
+";
+
+#[test]
+fn overloads_are_selected_by_arity() {
+    let source = format!("{{ {} area(2); area(2, 3); }}", AREA);
+    assert_eq!(
+        selected(&source),
+        ["area(side: Int)", "area(width: Int, height: Int)"],
+    );
+}
+
+#[test]
+fn overloads_are_selected_by_label() {
+    let source = format!("{{ {} area(radius = 2); area(2); }}", AREA);
+    assert_eq!(
+        selected(&source),
+        ["area(*, radius: Int)", "area(side: Int)"],
+    );
+}
+
+#[test]
+fn overloads_are_selected_by_type() {
+    let source = format!("{{ {} area(true); area(2); }}", AREA);
+    assert_eq!(selected(&source), ["area(inside: Bool)", "area(side: Int)"],);
+}
+
+#[test]
+fn overloads_leaving_fewer_parameters_to_their_defaults_are_preferred() {
+    let source = "{
+        function pad(width: Int, fill: Char = ' ', align: Bool = true) = { width }
+        function pad(width: Int, fill: Char) = { width }
+        pad(3, 'x');
+        pad(3);
+    }";
+    assert_eq!(
+        selected(source),
+        [
+            "pad(width: Int, fill: Char)",
+            "pad(width: Int, fill: Char = …, align: Bool = …)"
+        ],
+    );
+}
+
+#[test]
+fn calls_matching_several_overloads_are_ambiguous() {
+    let source = "{
+        function show(value: Int, precise: Bool = true) = { value }
+        function show(value: Int, width: Int = 0) = { value }
+        show(3)
+    }";
+    assert_eq!(
+        error(source),
+        (
+            "this call of `show` is ambiguous".to_string(),
+            vec![
+                "`show(value: Int, precise: Bool = …)` matches".to_string(),
+                "`show(value: Int, width: Int = …)` matches".to_string(),
+                "add labels or type annotations to select one overload".to_string(),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn calls_matching_no_overload_list_why_each_does_not_match() {
+    let source = format!("{{ {} area(2, 3, 4) }}", AREA);
+    assert_eq!(
+        error(&source),
+        (
+            "no overload of `area` matches these arguments".to_string(),
+            vec![
+                "`area(side: Int)` does not match: expected at most 1 positional argument, found 3"
+                    .to_string(),
+                "`area(width: Int, height: Int)` does not match: \
+                 expected at most 2 positional arguments, found 3"
+                    .to_string(),
+                "`area(*, radius: Int)` does not match: \
+                 expected at most 0 positional arguments, found 3"
+                    .to_string(),
+                "`area(inside: Bool)` does not match: expected at most 1 positional argument, found 3"
+                    .to_string(),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn calls_not_matching_the_only_declaration_say_why() {
+    let source = "{ function square(side: Int) = { mul(side, side) } square(true) }";
+    assert_eq!(
+        error(source),
+        (
+            "`side` expects a value of type `Int`, found `Bool`".to_string(),
+            vec!["`square(side: Int)` is declared here".to_string()],
+        ),
+    );
+}
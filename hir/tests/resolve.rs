@@ -0,0 +1,86 @@
+//! Checks that names resolve to the definitions in scope, and the diagnostics of those that do not.
+
+use nafi_hir::{diagnostics::Severity, lower, resolve, SourceId};
+
+/// The severity, source text, and message of each diagnostic of a script, in order.
+fn diagnostics(source: &str) -> Vec<(Severity, &str, String)> {
+    let function = nafi_parser::parse(source).expect("the script parses");
+    let (program, mut diagnostics) = lower::lower_script(SourceId(0), &function);
+    diagnostics.extend(resolve::resolve(&program).1);
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            let span = diagnostic.span.start as usize..diagnostic.span.end as usize;
+            (diagnostic.severity, &source[span], diagnostic.message)
+        })
+        .collect()
+}
+
+#[test]
+fn unresolved_names_are_reported_where_they_are_used() {
+    assert_eq!(
+        diagnostics("{ add(x, 1) }"),
+        [(
+            Severity::Error,
+            "x",
+            "cannot find `x` in this scope".to_string()
+        )],
+    );
+    assert_eq!(
+        diagnostics("{ module shapes { function area(side: Int) = { side } } shapes::volume(1) }"),
+        [(
+            Severity::Error,
+            "volume",
+            "cannot find `volume` in `shapes`".to_string()
+        )],
+    );
+}
+
+#[test]
+fn shadowing_a_definition_in_scope_is_warned_about() {
+    assert_eq!(
+        diagnostics("{ function f(x: Int) = { { x: Int -> x } } }"),
+        [(
+            Severity::Warning,
+            "x",
+            "`x` shadows a definition that is in scope".to_string(),
+        )],
+    );
+}
+
+#[test]
+fn shadowing_the_prelude_is_not_warned_about() {
+    assert_eq!(diagnostics("{ function f(print: Int) = { print } }"), [],);
+}
+
+#[test]
+fn cyclic_imports_are_reported() {
+    assert_eq!(
+        diagnostics("{ use b as a; use a as b; }"),
+        [(Severity::Error, "b", "cyclic import of `a`".to_string())],
+    );
+}
+
+#[test]
+fn only_items_can_be_imported() {
+    assert_eq!(
+        diagnostics("{ function f(x: Int) = { use x as y; y } }"),
+        [(
+            Severity::Error,
+            "x",
+            "only items can be imported".to_string()
+        )],
+    );
+}
+
+#[test]
+fn declarations_cannot_capture_locals_of_enclosing_functions() {
+    assert_eq!(
+        diagnostics("{ function f(x: Int) = { function g() = { x } g() } }"),
+        [(
+            Severity::Error,
+            "x",
+            "cannot use `x` of an enclosing function here".to_string(),
+        )],
+    );
+}
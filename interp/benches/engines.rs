@@ -0,0 +1,80 @@
+//! Compares the tree-walking interpreter with the bytecode virtual machine,
+//! by timing each on the scripts in `benches/programs`.
+//!
+//! Run with `cargo bench -p nafi-interp`. The bytecode time includes compiling.
+
+use nafi_hir::Analysis;
+use nafi_interp::{Engine, RuntimeError, Value};
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// How many times each program is run by each engine. The fastest run is reported.
+const RUNS: u32 = 20;
+
+fn analyze(path: &Path) -> Analysis {
+    let source = fs::read_to_string(path).expect("could not read the program");
+    let name = path.to_str().unwrap_or("<program>");
+    match nafi_interp::analyze_script(&source) {
+        Ok((analysis, _)) => analysis,
+        Err(nafi_interp::Error::Compile(diagnostics)) => {
+            for diagnostic in diagnostics.iter().filter(|d| d.is_error()) {
+                eprintln!("{}", diagnostic.render(&[(name, &source)]));
+            }
+            panic!("{} has errors", name)
+        }
+        Err(error) => panic!("could not analyze {}: {}", name, error),
+    }
+}
+
+/// The value of the program, and the fastest time it was computed in.
+fn time(mut run: impl FnMut() -> Result<Value, RuntimeError>) -> (Value, Duration) {
+    let mut fastest = Duration::from_secs(u64::MAX);
+    let mut value = Value::Unit;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        value = run().expect("the program stopped with an error");
+        fastest = fastest.min(start.elapsed());
+    }
+    (value, fastest)
+}
+
+fn main() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/programs");
+    let mut paths: Vec<_> = fs::read_dir(directory)
+        .expect("could not list the programs")
+        .map(|entry| entry.expect("could not list the programs").path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "nafi")
+        })
+        .collect();
+    paths.sort();
+
+    println!(
+        "{:<12} {:>14} {:>14} {:>8}",
+        "program", "tree-walking", "bytecode", "speedup",
+    );
+    for path in paths {
+        let analysis = analyze(&path);
+        let (walked, walking) = time(|| Engine::Walk.run(&analysis));
+        let (executed, bytecode) = time(|| Engine::Bytecode.run(&analysis));
+        assert_eq!(
+            walked,
+            executed,
+            "the engines disagree on {}",
+            path.display()
+        );
+        println!(
+            "{:<12} {:>14?} {:>14?} {:>7.2}x",
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("?"),
+            walking,
+            bytecode,
+            walking.as_secs_f64() / bytecode.as_secs_f64(),
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! The bytecode programs are compiled to, and its disassembly.
+//!
+//! Each function declaration and function expression compiles to a [`Chunk`] of
+//! instructions for a stack machine. A call's arguments and the bindings of its body
+//! live in numbered slots at the bottom of its frame, with the operand stack above them.
+//!
+//! Instructions are [`Op`]s, each eight bytes, whose operands index the slots,
+//! the chunk's tables, or the program's [`Code::functions`] and [`Code::shapes`].
+//! A native function of the prelude compiles to a chunk that calls it.
+//! Jumps are to the index of an instruction in the same chunk.
+
+use crate::{prelude::Native, value::Value};
+use nafi_hir::{HirId, Span};
+use std::{collections::HashMap, fmt, rc::Rc};
+
+/// A compiled program.
+#[derive(Clone, Debug)]
+pub struct Code {
+    /// Every function declaration and function expression of the program.
+    pub functions: Vec<Chunk>,
+    /// The index of the main function in [`Code::functions`].
+    pub main: u32,
+    /// Every record type and enum variant of the program.
+    pub shapes: Vec<Shape>,
+    /// The native functions [`Op::Native`] calls.
+    pub(crate) natives: Vec<Native>,
+    pub(crate) function_indices: HashMap<HirId, u32>,
+    pub(crate) shape_indices: HashMap<HirId, u32>,
+}
+
+/// The instructions of a function.
+#[derive(Clone, Debug)]
+pub struct Chunk {
+    /// The id of the function declaration or function expression.
+    pub id: HirId,
+    /// The name of the function, or `closure` for an unlabelled closure.
+    pub name: String,
+    /// Whether each parameter has a default, in the order declared.
+    ///
+    /// The parameters are bound to the first slots.
+    pub defaults: Vec<bool>,
+    /// How many slots a call of the function needs, including its parameters.
+    pub slots: u16,
+    /// Where the upvalues of a closure of the function are captured from,
+    /// in the frame that creates it.
+    pub captures: Vec<Capture>,
+    /// The values [`Op::Constant`] pushes.
+    pub constants: Vec<Value>,
+    /// The field names [`Op::Field`] gets, and the messages [`Op::Error`] raises.
+    pub names: Vec<String>,
+    /// The calls of function declarations [`Op::CallDeclared`] makes.
+    pub calls: Vec<DeclaredCall>,
+    /// The instructions.
+    pub code: Vec<Op>,
+    /// The span of the source each instruction was compiled from, for errors.
+    pub spans: Vec<Span>,
+}
+
+/// Where an upvalue is captured from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Capture {
+    /// A slot of the frame creating the closure.
+    Slot(u16),
+    /// An upvalue of the closure creating the closure.
+    Upvalue(u16),
+}
+
+/// A call of a function declaration, bound as overload resolution selected.
+#[derive(Clone, Debug)]
+pub struct DeclaredCall {
+    /// The index of the called function in [`Code::functions`].
+    pub function: u32,
+    /// Whether the call gives an argument for each parameter, or leaves it to its default.
+    ///
+    /// The arguments are on the stack in the order of the parameters they bind.
+    pub given: Rc<[bool]>,
+}
+
+/// A record type or enum variant.
+#[derive(Clone, Debug)]
+pub struct Shape {
+    /// The id of the record type or enum variant.
+    pub id: HirId,
+    /// The name of the record type, or of the variant as `Enum::Variant`.
+    pub name: String,
+    /// The names of the fields, in the order they are declared.
+    pub fields: Vec<String>,
+    /// Whether this is an enum variant.
+    pub variant: bool,
+}
+
+/// An instruction.
+///
+/// Instructions that pop several values pop them in the order they were pushed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Op {
+    /// Push a constant of the chunk.
+    Constant(u32),
+    /// Push `()`.
+    Unit,
+    /// Push `true`.
+    True,
+    /// Push `false`.
+    False,
+    /// Push the value of a slot.
+    Slot(u16),
+    /// Pop a value into a slot.
+    SetSlot(u16),
+    /// Push the value of an upvalue of the running closure.
+    Upvalue(u16),
+    /// Pop and discard a value.
+    Pop,
+    /// Discard values until the operand stack has this many.
+    SetDepth(u16),
+    /// Pop this many values into a tuple.
+    Tuple(u16),
+    /// Pop this many values into a `List`.
+    List(u32),
+    /// Pop the fields of a shape, in the order they are declared, into a value of it.
+    Construct(u32),
+    /// Create a closure of a function, capturing its upvalues.
+    Closure(u32),
+    /// Replace a tuple with an element, or a record or variant with a field, by position.
+    Element(u16),
+    /// Replace a record or variant with the field of a name of the chunk.
+    Field(u32),
+    /// Pop an index and a `List`, and push the element at the index.
+    Index,
+    /// Pop two values, and push whether they are equal.
+    Equal,
+    /// Replace a value with whether it is a value of a shape.
+    IsShape(u32),
+    /// Jump to an instruction.
+    Jump(u32),
+    /// Pop a `Bool`, and jump to an instruction if it is `false`.
+    JumpIfFalse(u32),
+    /// Jump to an instruction if the call gave an argument for the parameter.
+    JumpIfGiven(u16, u32),
+    /// Pop a `List` to iterate over into a slot, and start at its first element
+    /// by setting the next slot to `0`.
+    Iterate(u16),
+    /// Push the next element of the `List` iterated over in a slot,
+    /// or jump to an instruction if there are no more.
+    Next(u16, u32),
+    /// Call the value below this many arguments.
+    Call(u16),
+    /// Make a call of a function declaration of the chunk.
+    CallDeclared(u32),
+    /// Return the popped value from the running function.
+    Return,
+    /// Return the popped value from the call of a function enclosing the running closure
+    /// that the closure was created in, exiting any calls it made.
+    ///
+    /// The function is given by how deeply it is nested, `0` being a function declaration
+    /// or the main function, and by its index.
+    ReturnFrom(u16, u32),
+    /// Raise the error that no arm of a `match` matches the value of a slot.
+    NoMatch(u16),
+    /// Raise the error that the value of a slot does not match a pattern.
+    Mismatch(u16),
+    /// Raise an error with a message of the chunk.
+    Error(u32),
+    /// Push the value of a native function of the program, called with the values
+    /// of the parameters. This is the body of the declaration of a native function.
+    Native(u32),
+}
+
+impl Code {
+    pub(crate) fn function(&self, id: HirId) -> u32 {
+        self.function_indices[&id]
+    }
+
+    pub(crate) fn shape(&self, id: HirId) -> u32 {
+        self.shape_indices[&id]
+    }
+}
+
+impl fmt::Display for Code {
+    /// Disassembles the program, one chunk after another.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, chunk) in self.functions.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:>4} ", index)?;
+            chunk.disassemble(self, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Chunk {
+    fn disassemble(&self, code: &Code, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} ({} parameters, {} slots, {} upvalues)",
+            self.name,
+            self.defaults.len(),
+            self.slots,
+            self.captures.len(),
+        )?;
+        for (index, capture) in self.captures.iter().enumerate() {
+            match capture {
+                Capture::Slot(slot) => writeln!(f, "     ^{} = slot {}", index, slot)?,
+                Capture::Upvalue(upvalue) => writeln!(f, "     ^{} = ^{}", index, upvalue)?,
+            }
+        }
+        for (index, op) in self.code.iter().enumerate() {
+            write!(f, "{:>8}  ", index)?;
+            self.instruction(*op, code, f)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+
+    fn instruction(&self, op: Op, code: &Code, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let function = |index: u32| &code.functions[index as usize].name;
+        match op {
+            Op::Constant(index) => write!(f, "constant {}", self.constants[index as usize]),
+            Op::Unit => f.write_str("unit"),
+            Op::True => f.write_str("true"),
+            Op::False => f.write_str("false"),
+            Op::Slot(slot) => write!(f, "slot {}", slot),
+            Op::SetSlot(slot) => write!(f, "set slot {}", slot),
+            Op::Upvalue(upvalue) => write!(f, "upvalue ^{}", upvalue),
+            Op::Pop => f.write_str("pop"),
+            Op::SetDepth(depth) => write!(f, "set depth {}", depth),
+            Op::Tuple(length) => write!(f, "tuple {}", length),
+            Op::List(length) => write!(f, "list {}", length),
+            Op::Construct(shape) => write!(f, "construct {}", code.shapes[shape as usize].name),
+            Op::Closure(index) => write!(f, "closure {} {}", index, function(index)),
+            Op::Element(position) => write!(f, "element {}", position),
+            Op::Field(name) => write!(f, "field {}", self.names[name as usize]),
+            Op::Index => f.write_str("index"),
+            Op::Equal => f.write_str("equal"),
+            Op::IsShape(shape) => write!(f, "is {}", code.shapes[shape as usize].name),
+            Op::Jump(target) => write!(f, "jump {}", target),
+            Op::JumpIfFalse(target) => write!(f, "jump if false {}", target),
+            Op::JumpIfGiven(parameter, target) => {
+                write!(f, "jump if given {} {}", parameter, target)
+            }
+            Op::Iterate(slot) => write!(f, "iterate slot {}", slot),
+            Op::Next(slot, target) => write!(f, "next slot {} or jump {}", slot, target),
+            Op::Call(arguments) => write!(f, "call {}", arguments),
+            Op::CallDeclared(index) => {
+                let call = &self.calls[index as usize];
+                write!(f, "call {} {} (", call.function, function(call.function))?;
+                for (index, given) in call.given.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    f.write_str(if *given { "given" } else { "default" })?;
+                }
+                f.write_str(")")
+            }
+            Op::Return => f.write_str("return"),
+            Op::ReturnFrom(_, index) => write!(f, "return from {} {}", index, function(index)),
+            Op::NoMatch(slot) => write!(f, "no match slot {}", slot),
+            Op::Mismatch(slot) => write!(f, "mismatch slot {}", slot),
+            Op::Error(message) => write!(f, "error {:?}", self.names[message as usize]),
+            Op::Native(_) => write!(f, "native {}", self.name),
+        }
+    }
+}
@@ -0,0 +1,781 @@
+//! Compilation of the HIR to bytecode.
+//!
+//! Each function is compiled in one pass over its body. The compiler tracks how many
+//! values are on the operand stack after each instruction, so that `break` and
+//! `continue` can discard those pushed inside the loop before they jump.
+
+use crate::{
+    bytecode::{Capture, Chunk, Code, DeclaredCall, Op, Shape},
+    prelude,
+    value::{Record, Value},
+};
+use nafi_hir::{
+    expressions::{Block, Closure as ClosureExpr, Expr, ExprKind, Literal, Stmt},
+    items::{Function, Item},
+    overload::ParameterBinding,
+    paths::Path,
+    patterns::{Pattern, PatternKind},
+    prelude::is_native,
+    resolve::Res,
+    visit::{self, Visitor},
+    Analysis, HirId, Span,
+};
+use std::{collections::HashMap, rc::Rc};
+
+pub(crate) struct Compiler<'h> {
+    analysis: &'h Analysis,
+    code: Code,
+    declarations: Vec<&'h Function>,
+    /// The functions being compiled, innermost last.
+    ///
+    /// Only function expressions are nested, as a function declaration cannot capture.
+    states: Vec<State>,
+}
+
+/// A function being compiled.
+struct State {
+    index: u32,
+    chunk: Chunk,
+    slots: HashMap<HirId, u16>,
+    upvalues: HashMap<HirId, u16>,
+    /// How many values are on the operand stack.
+    depth: i32,
+    loops: Vec<Loop>,
+}
+
+/// A loop being compiled.
+struct Loop {
+    id: HirId,
+    /// The depth of the operand stack outside the loop.
+    depth: i32,
+    /// The slot holding the value of a `loop`. A `for` loop has the value `()`.
+    result: Option<u16>,
+    /// Where `continue` jumps to.
+    next: u32,
+    /// The jumps of each `break`, to patch with the end of the loop.
+    breaks: Vec<usize>,
+}
+
+/// Indexes the declarations and record types of the program.
+impl<'h> Visitor<'h> for Compiler<'h> {
+    fn visit_item(&mut self, item: &'h Item) {
+        match item {
+            Item::Type(r#type) => {
+                let fields = r#type.fields.iter().map(|f| f.name.text.clone()).collect();
+                self.shape(r#type.id, r#type.name.text.clone(), fields, false);
+            }
+            Item::Enum(r#enum) => {
+                for variant in &r#enum.variants {
+                    let name = format!("{}::{}", r#enum.name.text, variant.name.text);
+                    let fields = variant.fields.iter().map(|f| f.name.text.clone()).collect();
+                    self.shape(variant.id, name, fields, true);
+                }
+            }
+            _ => visit::walk_item(self, item),
+        }
+    }
+
+    fn visit_function(&mut self, function: &'h Function) {
+        let index = self.reserve(function.id, function.name.text.clone());
+        debug_assert_eq!(index as usize, self.declarations.len());
+        self.declarations.push(function);
+        visit::walk_function(self, function);
+    }
+}
+
+impl<'h> Compiler<'h> {
+    pub(crate) fn new(analysis: &'h Analysis) -> Self {
+        let mut compiler = Compiler {
+            analysis,
+            code: Code {
+                functions: vec![],
+                main: 0,
+                shapes: vec![],
+                natives: vec![],
+                function_indices: HashMap::new(),
+                shape_indices: HashMap::new(),
+            },
+            declarations: vec![],
+            states: vec![],
+        };
+        visit::walk_program(&mut compiler, &analysis.program);
+        compiler
+    }
+
+    /// Compile every function of the program.
+    pub(crate) fn compile(mut self) -> Code {
+        for index in 0..self.declarations.len() {
+            self.declaration(index as u32, self.declarations[index]);
+        }
+        let main = &self.analysis.program.main;
+        self.code.main = match &main.kind {
+            ExprKind::Closure(closure) => self.closure(main.id, "main".to_string(), closure),
+            _ => unreachable!("main function is not a closure"),
+        };
+        self.code
+    }
+
+    fn shape(&mut self, id: HirId, name: String, fields: Vec<String>, variant: bool) {
+        let index = self.code.shapes.len() as u32;
+        self.code.shapes.push(Shape {
+            id,
+            name,
+            fields,
+            variant,
+        });
+        self.code.shape_indices.insert(id, index);
+    }
+
+    fn res(&self, path: &Path) -> &'h Res {
+        self.analysis.resolutions.get(path).unwrap_or(&Res::Err)
+    }
+
+    // Functions
+
+    /// Add an empty chunk for a function, to compile later.
+    fn reserve(&mut self, id: HirId, name: String) -> u32 {
+        let index = self.code.functions.len() as u32;
+        self.code.functions.push(Chunk {
+            id,
+            name,
+            defaults: vec![],
+            slots: 0,
+            captures: vec![],
+            constants: vec![],
+            names: vec![],
+            calls: vec![],
+            code: vec![],
+            spans: vec![],
+        });
+        self.code.function_indices.insert(id, index);
+        index
+    }
+
+    /// Start compiling the reserved chunk of a function, with a slot for each parameter.
+    fn enter(&mut self, index: u32, defaults: Vec<bool>) {
+        let mut chunk = self.code.functions[index as usize].clone();
+        chunk.slots = defaults.len() as u16;
+        chunk.defaults = defaults;
+        self.states.push(State {
+            index,
+            chunk,
+            slots: HashMap::new(),
+            upvalues: HashMap::new(),
+            depth: 0,
+            loops: vec![],
+        });
+    }
+
+    /// Finish compiling the innermost function, returning the value of its body.
+    fn exit(&mut self, body: &'h Block) {
+        self.block(body);
+        self.emit(Op::Return, body.span);
+        self.finish();
+    }
+
+    /// Finish compiling the innermost function.
+    fn finish(&mut self) {
+        let state = self.states.pop().expect("no function is being compiled");
+        self.code.functions[state.index as usize] = state.chunk;
+    }
+
+    fn declaration(&mut self, index: u32, function: &'h Function) {
+        let defaults = function
+            .parameters
+            .iter()
+            .map(|parameter| parameter.default.is_some())
+            .collect();
+        self.enter(index, defaults);
+        if is_native(function) {
+            let native = prelude::native(&function.name.text).expect("native function");
+            let native_index = self.code.natives.len() as u32;
+            self.code.natives.push(native);
+            self.emit(Op::Native(native_index), function.span);
+            self.emit(Op::Return, function.span);
+            self.finish();
+            return;
+        }
+        for (slot, parameter) in function.parameters.iter().enumerate() {
+            let slot = slot as u16;
+            if let Some(default) = &parameter.default {
+                let given = self.emit(Op::JumpIfGiven(slot, 0), parameter.span);
+                self.expr(default);
+                self.emit(Op::SetSlot(slot), parameter.span);
+                self.patch(given);
+            }
+            self.bind(&parameter.pattern, slot);
+        }
+        self.exit(&function.body);
+    }
+
+    /// Compile a function expression, returning the index of its chunk.
+    fn closure(&mut self, id: HirId, name: String, closure: &'h ClosureExpr) -> u32 {
+        let index = self.reserve(id, name);
+        self.enter(index, vec![false; closure.parameters.len()]);
+        for (slot, parameter) in closure.parameters.iter().enumerate() {
+            self.bind(&parameter.pattern, slot as u16);
+        }
+        self.exit(&closure.body);
+        index
+    }
+
+    // Emitting
+
+    fn state(&mut self) -> &mut State {
+        self.states
+            .last_mut()
+            .expect("no function is being compiled")
+    }
+
+    /// Append an instruction, returning its index.
+    fn emit(&mut self, op: Op, span: Span) -> usize {
+        let state = self
+            .states
+            .last_mut()
+            .expect("no function is being compiled");
+        let effect = match op {
+            Op::Constant(_)
+            | Op::Unit
+            | Op::True
+            | Op::False
+            | Op::Slot(_)
+            | Op::Upvalue(_)
+            | Op::Closure(_)
+            | Op::Next(..) => 1,
+            // An error stands in for the value of the expression that raises it.
+            Op::Error(_) | Op::NoMatch(_) => 1,
+            Op::Native(_) => 1,
+            Op::SetSlot(_) | Op::Pop | Op::Index | Op::Equal | Op::JumpIfFalse(_) => -1,
+            Op::Iterate(_) | Op::Return | Op::ReturnFrom(..) => -1,
+            Op::Element(_) | Op::Field(_) | Op::IsShape(_) | Op::Mismatch(_) => 0,
+            Op::Jump(_) | Op::JumpIfGiven(..) => 0,
+            Op::SetDepth(depth) => i32::from(depth) - state.depth,
+            Op::Tuple(length) => 1 - i32::from(length),
+            Op::List(length) => 1 - length as i32,
+            Op::Construct(shape) => 1 - self.code.shapes[shape as usize].fields.len() as i32,
+            Op::Call(arguments) => -i32::from(arguments),
+            Op::CallDeclared(call) => {
+                let given = &state.chunk.calls[call as usize].given;
+                1 - given.iter().filter(|&&given| given).count() as i32
+            }
+        };
+        state.depth += effect;
+        state.chunk.code.push(op);
+        state.chunk.spans.push(span);
+        state.chunk.code.len() - 1
+    }
+
+    /// Point the jump at an index to the next instruction.
+    fn patch(&mut self, at: usize) {
+        let chunk = &mut self.state().chunk;
+        let next = chunk.code.len() as u32;
+        match &mut chunk.code[at] {
+            Op::Jump(target)
+            | Op::JumpIfFalse(target)
+            | Op::JumpIfGiven(_, target)
+            | Op::Next(_, target) => *target = next,
+            op => unreachable!("patched {:?}", op),
+        }
+    }
+
+    /// Allocate a new slot.
+    fn slot(&mut self) -> u16 {
+        let chunk = &mut self.state().chunk;
+        chunk.slots += 1;
+        chunk.slots - 1
+    }
+
+    fn constant(&mut self, value: Value, span: Span) {
+        let constants = &mut self.state().chunk.constants;
+        constants.push(value);
+        let index = constants.len() as u32 - 1;
+        self.emit(Op::Constant(index), span);
+    }
+
+    fn name(&mut self, name: &str) -> u32 {
+        let names = &mut self.state().chunk.names;
+        match names.iter().position(|known| known == name) {
+            Some(index) => index as u32,
+            None => {
+                names.push(name.to_string());
+                names.len() as u32 - 1
+            }
+        }
+    }
+
+    fn error(&mut self, span: Span, message: impl Into<String>) {
+        let message = self.name(&message.into());
+        self.emit(Op::Error(message), span);
+    }
+
+    // Bindings
+
+    /// The instruction pushing the value of a local binding.
+    fn local(&mut self, binding: HirId) -> Option<Op> {
+        let level = self.states.len() - 1;
+        Some(match self.capture(level, binding)? {
+            Capture::Slot(slot) => Op::Slot(slot),
+            Capture::Upvalue(upvalue) => Op::Upvalue(upvalue),
+        })
+    }
+
+    /// Where a local binding is, in the function being compiled at a level,
+    /// capturing it from the enclosing functions if needed.
+    fn capture(&mut self, level: usize, binding: HirId) -> Option<Capture> {
+        let state = &self.states[level];
+        if let Some(&slot) = state.slots.get(&binding) {
+            return Some(Capture::Slot(slot));
+        }
+        if let Some(&upvalue) = state.upvalues.get(&binding) {
+            return Some(Capture::Upvalue(upvalue));
+        }
+        if level == 0 {
+            return None;
+        }
+        let capture = self.capture(level - 1, binding)?;
+        let state = &mut self.states[level];
+        let upvalue = state.chunk.captures.len() as u16;
+        state.chunk.captures.push(capture);
+        state.upvalues.insert(binding, upvalue);
+        Some(Capture::Upvalue(upvalue))
+    }
+
+    /// Bind the names of an irrefutable pattern to the value in a slot.
+    fn bind(&mut self, pattern: &'h Pattern, slot: u16) {
+        let mut fails = vec![];
+        self.pattern(pattern, slot, &mut fails);
+        if !fails.is_empty() {
+            let matched = self.emit(Op::Jump(0), pattern.span);
+            for fail in fails {
+                self.patch(fail);
+            }
+            self.emit(Op::Mismatch(slot), pattern.span);
+            self.patch(matched);
+        }
+    }
+
+    /// Match the value in a slot against a pattern, binding its names.
+    ///
+    /// The jumps taken if it does not match are added to `fails`.
+    fn pattern(&mut self, pattern: &'h Pattern, slot: u16, fails: &mut Vec<usize>) {
+        let span = pattern.span;
+        match &pattern.kind {
+            PatternKind::Wildcard => {}
+            PatternKind::Binding(_) => {
+                self.state().slots.insert(pattern.id, slot);
+            }
+            PatternKind::Literal(literal) => {
+                self.emit(Op::Slot(slot), span);
+                self.literal(literal, span);
+                self.emit(Op::Equal, span);
+                fails.push(self.emit(Op::JumpIfFalse(0), span));
+            }
+            PatternKind::Tuple(patterns) => {
+                for (position, pattern) in patterns.iter().enumerate() {
+                    self.emit(Op::Slot(slot), span);
+                    self.emit(Op::Element(position as u16), span);
+                    self.subpattern(pattern, fails);
+                }
+            }
+            PatternKind::Constructor { path, fields } => {
+                if !self.is_shape(path, slot, fails) {
+                    return;
+                }
+                for (position, pattern) in fields.iter().enumerate() {
+                    self.emit(Op::Slot(slot), span);
+                    self.emit(Op::Element(position as u16), span);
+                    self.subpattern(pattern, fails);
+                }
+            }
+            PatternKind::Record { path, fields } => {
+                if !self.is_shape(path, slot, fails) {
+                    return;
+                }
+                for field in fields {
+                    let name = self.name(&field.name.text);
+                    self.emit(Op::Slot(slot), field.span);
+                    self.emit(Op::Field(name), field.span);
+                    self.subpattern(&field.pattern, fails);
+                }
+            }
+        }
+    }
+
+    /// Match the popped value against a pattern nested in another.
+    fn subpattern(&mut self, pattern: &'h Pattern, fails: &mut Vec<usize>) {
+        let slot = self.slot();
+        self.emit(Op::SetSlot(slot), pattern.span);
+        self.pattern(pattern, slot, fails);
+    }
+
+    /// Test that the value in a slot is of the record type or variant of a path,
+    /// returning whether it could be.
+    fn is_shape(&mut self, path: &Path, slot: u16, fails: &mut Vec<usize>) -> bool {
+        match *self.res(path) {
+            Res::Type(id) | Res::Variant(id) => {
+                let shape = self.code.shape(id);
+                self.emit(Op::Slot(slot), path.span);
+                self.emit(Op::IsShape(shape), path.span);
+                fails.push(self.emit(Op::JumpIfFalse(0), path.span));
+                true
+            }
+            _ => {
+                fails.push(self.emit(Op::Jump(0), path.span));
+                false
+            }
+        }
+    }
+
+    // Expressions
+
+    fn block(&mut self, block: &'h Block) {
+        for statement in &block.statements {
+            if let Stmt::Expr(expr) = statement {
+                self.expr(expr);
+                self.emit(Op::Pop, expr.span);
+            }
+        }
+        match &block.tail {
+            Some(tail) => self.expr(tail),
+            None => {
+                self.emit(Op::Unit, block.span);
+            }
+        }
+    }
+
+    fn optional(&mut self, expr: Option<&'h Expr>, span: Span) {
+        match expr {
+            Some(expr) => self.expr(expr),
+            None => {
+                self.emit(Op::Unit, span);
+            }
+        }
+    }
+
+    /// Compile an expression, which pushes its value.
+    fn expr(&mut self, expr: &'h Expr) {
+        let span = expr.span;
+        match &expr.kind {
+            ExprKind::Literal(literal) => self.literal(literal, span),
+            ExprKind::Path(path) => self.path(path),
+            ExprKind::Tuple(elements) => {
+                for element in elements {
+                    self.expr(element);
+                }
+                self.emit(Op::Tuple(elements.len() as u16), span);
+            }
+            ExprKind::List(elements) => {
+                for element in elements {
+                    self.expr(element);
+                }
+                self.emit(Op::List(elements.len() as u32), span);
+            }
+            ExprKind::Record { path, fields } => {
+                let shape = match *self.res(path) {
+                    Res::Type(id) | Res::Variant(id) => self.code.shape(id),
+                    _ => return self.error(path.span, format!("`{}` is not a record type", path)),
+                };
+                let declared = self.code.shapes[shape as usize].fields.clone();
+                let in_order = fields.len() == declared.len()
+                    && fields
+                        .iter()
+                        .zip(&declared)
+                        .all(|(field, name)| field.name.text == *name);
+                if in_order {
+                    for field in fields {
+                        self.expr(&field.value);
+                    }
+                } else {
+                    // Evaluate the fields as written, then push them as declared.
+                    let mut slots = vec![];
+                    for field in fields {
+                        self.expr(&field.value);
+                        let slot = self.slot();
+                        self.emit(Op::SetSlot(slot), field.span);
+                        slots.push((&field.name.text, slot));
+                    }
+                    for name in &declared {
+                        match slots.iter().find(|(field, _)| *field == name) {
+                            Some(&(_, slot)) => {
+                                self.emit(Op::Slot(slot), span);
+                            }
+                            None => self.error(span, format!("missing field `{}`", name)),
+                        }
+                    }
+                }
+                self.emit(Op::Construct(shape), span);
+            }
+            ExprKind::Closure(closure) => {
+                let name = match &closure.label {
+                    Some(label) => label.text.clone(),
+                    None => "closure".to_string(),
+                };
+                let index = self.closure(expr.id, name, closure);
+                self.emit(Op::Closure(index), span);
+            }
+            ExprKind::Call {
+                callee, arguments, ..
+            } => {
+                let selection = self.analysis.typing.overloads().get(expr);
+                if let (ExprKind::Path(_), Some(selection)) = (&callee.kind, selection) {
+                    for argument in arguments {
+                        self.expr(&argument.value);
+                    }
+                    let given = selection
+                        .bindings
+                        .iter()
+                        .map(|binding| match binding {
+                            ParameterBinding::Argument(_) => true,
+                            ParameterBinding::Default => false,
+                            ParameterBinding::Variadic(_) | ParameterBinding::TrailingClosure => {
+                                unreachable!("call not desugared")
+                            }
+                        })
+                        .collect();
+                    let function = self.code.function(selection.function);
+                    let calls = &mut self.state().chunk.calls;
+                    calls.push(DeclaredCall { function, given });
+                    let call = calls.len() as u32 - 1;
+                    self.emit(Op::CallDeclared(call), span);
+                    return;
+                }
+                if let ExprKind::Path(path) = &callee.kind {
+                    if let Res::Variant(id) = *self.res(path) {
+                        let shape = self.code.shape(id);
+                        if self.code.shapes[shape as usize].fields.len() == arguments.len() {
+                            for argument in arguments {
+                                self.expr(&argument.value);
+                            }
+                            self.emit(Op::Construct(shape), span);
+                            return;
+                        }
+                    }
+                }
+                self.expr(callee);
+                for argument in arguments {
+                    self.expr(&argument.value);
+                }
+                self.emit(Op::Call(arguments.len() as u16), span);
+            }
+            ExprKind::MethodCall { .. } => unreachable!("method calls are desugared"),
+            ExprKind::Field { receiver, field } => {
+                self.expr(receiver);
+                let name = self.name(&field.text);
+                self.emit(Op::Field(name), field.span);
+            }
+            ExprKind::Index { receiver, index } => {
+                self.expr(receiver);
+                self.expr(index);
+                self.emit(Op::Index, index.span);
+            }
+            ExprKind::Block(block) => self.block(block),
+            ExprKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(condition);
+                let otherwise = self.emit(Op::JumpIfFalse(0), condition.span);
+                self.block(then_branch);
+                let end = self.emit(Op::Jump(0), span);
+                self.state().depth -= 1;
+                self.patch(otherwise);
+                match else_branch {
+                    Some(else_branch) => self.block(else_branch),
+                    None => {
+                        self.emit(Op::Unit, span);
+                    }
+                }
+                self.patch(end);
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                self.expr(scrutinee);
+                let slot = self.slot();
+                self.emit(Op::SetSlot(slot), scrutinee.span);
+                let mut ends = vec![];
+                for arm in arms {
+                    let mut fails = vec![];
+                    self.pattern(&arm.pattern, slot, &mut fails);
+                    if let Some(guard) = &arm.guard {
+                        self.expr(guard);
+                        fails.push(self.emit(Op::JumpIfFalse(0), guard.span));
+                    }
+                    self.expr(&arm.body);
+                    ends.push(self.emit(Op::Jump(0), arm.span));
+                    self.state().depth -= 1;
+                    for fail in fails {
+                        self.patch(fail);
+                    }
+                }
+                self.emit(Op::NoMatch(slot), scrutinee.span);
+                for end in ends {
+                    self.patch(end);
+                }
+            }
+            ExprKind::Loop { body, .. } => {
+                let result = self.slot();
+                let state = self.state();
+                let next = state.chunk.code.len() as u32;
+                let depth = state.depth;
+                state.loops.push(Loop {
+                    id: expr.id,
+                    depth,
+                    result: Some(result),
+                    next,
+                    breaks: vec![],
+                });
+                self.block(body);
+                self.emit(Op::Pop, body.span);
+                self.emit(Op::Jump(next), span);
+                self.end_loop();
+                self.emit(Op::Slot(result), span);
+            }
+            ExprKind::For {
+                pattern,
+                iterable,
+                body,
+                ..
+            } => {
+                self.expr(iterable);
+                let list = self.slot();
+                self.slot();
+                self.emit(Op::Iterate(list), iterable.span);
+                let next = self.state().chunk.code.len() as u32;
+                let depth = self.state().depth;
+                let exit = self.emit(Op::Next(list, 0), iterable.span);
+                let element = self.slot();
+                self.emit(Op::SetSlot(element), pattern.span);
+                self.bind(pattern, element);
+                self.state().loops.push(Loop {
+                    id: expr.id,
+                    depth,
+                    result: None,
+                    next,
+                    breaks: vec![],
+                });
+                self.block(body);
+                self.emit(Op::Pop, body.span);
+                self.emit(Op::Jump(next), span);
+                self.patch(exit);
+                self.end_loop();
+                self.emit(Op::Unit, span);
+            }
+            ExprKind::Break { target, value } => {
+                self.optional(value.as_deref(), span);
+                let (depth, result) = self.target(*target, |target| (target.depth, target.result));
+                match result {
+                    Some(result) => self.emit(Op::SetSlot(result), span),
+                    None => self.emit(Op::Pop, span),
+                };
+                self.set_depth(depth, span);
+                let jump = self.emit(Op::Jump(0), span);
+                self.target(*target, |target| target.breaks.push(jump));
+                self.state().depth = depth + 1;
+            }
+            ExprKind::Continue { target } => {
+                let before = self.state().depth;
+                let (depth, next) = self.target(*target, |target| (target.depth, target.next));
+                self.set_depth(depth, span);
+                self.emit(Op::Jump(next), span);
+                self.state().depth = before + 1;
+            }
+            ExprKind::Return { target, value } => {
+                let before = self.state().depth;
+                self.optional(value.as_deref(), span);
+                match self
+                    .states
+                    .iter()
+                    .rposition(|state| state.chunk.id == *target)
+                {
+                    Some(level) if level == self.states.len() - 1 => {
+                        self.emit(Op::Return, span);
+                    }
+                    Some(level) => {
+                        let index = self.states[level].index;
+                        self.emit(Op::ReturnFrom(level as u16, index), span);
+                    }
+                    None => unreachable!("return target is not an enclosing function"),
+                }
+                self.state().depth = before + 1;
+            }
+        }
+    }
+
+    /// The result of a function of the enclosing loop with an id.
+    fn target<T>(&mut self, id: HirId, f: impl FnOnce(&mut Loop) -> T) -> T {
+        let target = self
+            .state()
+            .loops
+            .iter_mut()
+            .rev()
+            .find(|target| target.id == id)
+            .expect("loop target is not an enclosing loop");
+        f(target)
+    }
+
+    /// Discard the values on the operand stack above a depth.
+    fn set_depth(&mut self, depth: i32, span: Span) {
+        if self.state().depth != depth {
+            self.emit(Op::SetDepth(depth as u16), span);
+        }
+    }
+
+    /// Finish compiling the innermost loop, pointing its `break`s here.
+    fn end_loop(&mut self) {
+        let target = self.state().loops.pop().expect("no loop is being compiled");
+        for jump in target.breaks {
+            self.patch(jump);
+        }
+        self.state().depth = target.depth;
+    }
+
+    fn literal(&mut self, literal: &Literal, span: Span) {
+        match literal {
+            Literal::Integer(digits) => {
+                let value = digits.parse().expect("integer literal of digits");
+                self.constant(Value::Int(value), span);
+            }
+            Literal::Boolean(true) => {
+                self.emit(Op::True, span);
+            }
+            Literal::Boolean(false) => {
+                self.emit(Op::False, span);
+            }
+            Literal::Character(value) => self.constant(Value::Char(*value), span),
+            Literal::Unit => {
+                self.emit(Op::Unit, span);
+            }
+        }
+    }
+
+    /// Push the value a path used as an expression refers to.
+    fn path(&mut self, path: &Path) {
+        match self.res(path) {
+            &Res::Local(binding) => match self.local(binding) {
+                Some(op) => {
+                    self.emit(op, path.span);
+                }
+                None => self.error(path.span, format!("`{}` is used before it is bound", path)),
+            },
+            Res::Functions(overloads) if overloads.len() == 1 => {
+                let index = self.code.function(overloads[0]);
+                let name = self.code.functions[index as usize].name.as_str().into();
+                self.constant(Value::Function(overloads[0], name), path.span);
+            }
+            &Res::Variant(id) => {
+                let shape = &self.code.shapes[self.code.shape(id) as usize];
+                let value = if shape.fields.is_empty() {
+                    Value::Variant(Rc::new(Record {
+                        id,
+                        name: shape.name.clone(),
+                        fields: vec![],
+                    }))
+                } else {
+                    Value::Constructor(id, shape.name.as_str().into())
+                };
+                self.constant(value, path.span);
+            }
+            _ => self.error(path.span, format!("`{}` is not a value", path)),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Errors that stop a running program.
+
+use nafi_hir::{diagnostics::Diagnostic, Span};
+use std::{error::Error, fmt};
+
+/// An error raised while running a program, such as indexing past the end of a list.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RuntimeError {
+    /// Where the error was raised.
+    pub span: Span,
+    /// What went wrong.
+    pub message: String,
+    /// The calls in progress when the error was raised, outermost first.
+    pub stack: Vec<Frame>,
+}
+
+/// A call in progress.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Frame {
+    /// The name of the called function, or `closure` for an unlabelled closure.
+    pub name: String,
+    /// The span of the call.
+    pub call: Span,
+}
+
+impl RuntimeError {
+    /// The error as a diagnostic, with a note for each call in progress, innermost first.
+    ///
+    /// Only the innermost calls of a deep stack are noted.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        const SHOWN: usize = 10;
+        let mut diagnostic = Diagnostic::error(self.span, self.message.clone());
+        for frame in self.stack.iter().rev().take(SHOWN) {
+            diagnostic =
+                diagnostic.with_note_at(frame.call, format!("in `{}`, called here", frame.name));
+        }
+        if self.stack.len() > SHOWN {
+            let message = format!("and {} more calls", self.stack.len() - SHOWN);
+            diagnostic = diagnostic.with_note(message);
+        }
+        diagnostic
+    }
+
+    /// Render the error for display, quoting the source files it refers to.
+    ///
+    /// See [`Diagnostic::render`].
+    pub fn render(&self, files: &[(&str, &str)]) -> String {
+        self.to_diagnostic().render(files)
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for RuntimeError {}
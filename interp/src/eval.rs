@@ -0,0 +1,664 @@
+//! Evaluation of the HIR by walking its tree.
+
+use crate::{
+    error::{Frame, RuntimeError},
+    prelude::{self, Native},
+    value::{Captures, Closure, Env, Record, Value},
+    MAX_DEPTH,
+};
+use nafi_hir::{
+    expressions::{Argument, Block, Closure as ClosureExpr, Expr, ExprKind, Literal, Stmt},
+    items::{EnumDef, Function, Item, TypeDef, Variant},
+    overload::ParameterBinding,
+    paths::Path,
+    patterns::{Pattern, PatternKind},
+    prelude::is_native,
+    resolve::Res,
+    visit::{self, Visitor},
+    Analysis, HirId, Span,
+};
+use std::{collections::HashMap, mem, rc::Rc};
+
+/// How much of the native stack must be left for evaluation to continue on it.
+const RED_ZONE: usize = 256 * 1024;
+
+/// How large each native stack evaluation grows onto is.
+const STACK_SEGMENT: usize = 8 * 1024 * 1024;
+
+/// Why evaluation of an expression did not produce a value.
+enum Unwind {
+    /// A `break` out of the loop, with its value.
+    Break(HirId, Value),
+    /// A `continue` of the loop.
+    Continue(HirId),
+    /// A `return` from the call of a function declaration or closure, by its number,
+    /// with its value.
+    Return(u64, Value),
+    /// A runtime error.
+    Error(RuntimeError),
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(error: RuntimeError) -> Self {
+        Unwind::Error(error)
+    }
+}
+
+type Eval<T = Value> = Result<T, Unwind>;
+
+fn literal_value(literal: &Literal) -> Value {
+    match literal {
+        Literal::Integer(digits) => Value::Int(digits.parse().expect("integer literal of digits")),
+        Literal::Boolean(value) => Value::Bool(*value),
+        Literal::Character(value) => Value::Char(*value),
+        Literal::Unit => Value::Unit,
+    }
+}
+
+pub(crate) struct Interpreter<'h> {
+    analysis: &'h Analysis,
+    functions: HashMap<HirId, &'h Function>,
+    natives: HashMap<HirId, Native>,
+    closures: HashMap<HirId, &'h ClosureExpr>,
+    records: HashMap<HirId, &'h TypeDef>,
+    variants: HashMap<HirId, (&'h EnumDef, &'h Variant)>,
+    stack: Vec<Frame>,
+    /// The number of each call in [`Interpreter::stack`]. Each call is numbered after
+    /// the calls before it, so a `return` exits only the call its closure was created in.
+    calls: Vec<u64>,
+    /// The number of the next call. The main function is call `0`.
+    next_call: u64,
+}
+
+/// Indexes the declarations and closures of the program by their ids.
+impl<'h> Visitor<'h> for Interpreter<'h> {
+    fn visit_item(&mut self, item: &'h Item) {
+        match item {
+            Item::Type(r#type) => {
+                self.records.insert(r#type.id, r#type);
+            }
+            Item::Enum(r#enum) => {
+                for variant in &r#enum.variants {
+                    self.variants.insert(variant.id, (r#enum, variant));
+                }
+            }
+            _ => visit::walk_item(self, item),
+        }
+    }
+
+    fn visit_function(&mut self, function: &'h Function) {
+        self.functions.insert(function.id, function);
+        if is_native(function) {
+            let native = prelude::native(&function.name.text).expect("native function");
+            self.natives.insert(function.id, native);
+        }
+        visit::walk_function(self, function);
+    }
+
+    fn visit_expr(&mut self, expr: &'h Expr) {
+        if let ExprKind::Closure(closure) = &expr.kind {
+            self.closures.insert(expr.id, closure);
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+impl<'h> Interpreter<'h> {
+    pub(crate) fn new(analysis: &'h Analysis) -> Self {
+        let mut interpreter = Interpreter {
+            analysis,
+            functions: HashMap::new(),
+            natives: HashMap::new(),
+            closures: HashMap::new(),
+            records: HashMap::new(),
+            variants: HashMap::new(),
+            stack: vec![],
+            calls: vec![],
+            next_call: 1,
+        };
+        visit::walk_program(&mut interpreter, &analysis.program);
+        interpreter
+    }
+
+    /// Run the main function of the program.
+    pub(crate) fn run(&mut self) -> Result<Value, RuntimeError> {
+        let main = &self.analysis.program.main;
+        let body = match &main.kind {
+            ExprKind::Closure(closure) => &closure.body,
+            _ => unreachable!("main function is not a closure"),
+        };
+        match self.block(body, &Env::call(main.id, 0, None)) {
+            Ok(value) | Err(Unwind::Return(0, value)) => Ok(value),
+            Err(Unwind::Error(error)) => Err(error),
+            Err(_) => unreachable!("unwound out of the main function"),
+        }
+    }
+
+    fn error(&self, span: Span, message: impl Into<String>) -> RuntimeError {
+        RuntimeError {
+            span,
+            message: message.into(),
+            stack: self.stack.clone(),
+        }
+    }
+
+    fn res(&self, path: &Path) -> &'h Res {
+        self.analysis.resolutions.get(path).unwrap_or(&Res::Err)
+    }
+
+    // Expressions
+
+    fn block(&mut self, block: &'h Block, env: &Rc<Env>) -> Eval {
+        for statement in &block.statements {
+            if let Stmt::Expr(expr) = statement {
+                self.eval(expr, env)?;
+            }
+        }
+        match &block.tail {
+            Some(tail) => self.eval(tail, env),
+            None => Ok(Value::Unit),
+        }
+    }
+
+    /// Evaluate an expression.
+    ///
+    /// Evaluation recurses on the native stack as deeply as calls and expressions nest,
+    /// so it continues on a new stack whenever the one it is on runs low, whatever
+    /// thread the program is run on.
+    fn eval(&mut self, expr: &'h Expr, env: &Rc<Env>) -> Eval {
+        stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || self.eval_expr(expr, env))
+    }
+
+    fn eval_expr(&mut self, expr: &'h Expr, env: &Rc<Env>) -> Eval {
+        Ok(match &expr.kind {
+            ExprKind::Literal(literal) => literal_value(literal),
+            ExprKind::Path(path) => self.path(path, env)?,
+            ExprKind::Tuple(elements) => Value::Tuple(Rc::new(self.all(elements, env)?)),
+            ExprKind::List(elements) => Value::List(Rc::new(self.all(elements, env)?)),
+            ExprKind::Record { path, fields } => {
+                let mut values = vec![];
+                for field in fields {
+                    values.push((&field.name.text, self.eval(&field.value, env)?));
+                }
+                let (id, name, declared) = match *self.res(path) {
+                    Res::Type(id) => {
+                        let r#type = self.records[&id];
+                        (id, r#type.name.text.clone(), &r#type.fields)
+                    }
+                    Res::Variant(id) => {
+                        let (r#enum, variant) = self.variants[&id];
+                        let name = format!("{}::{}", r#enum.name.text, variant.name.text);
+                        (id, name, &variant.fields)
+                    }
+                    _ => {
+                        let message = format!("`{}` is not a record type", path);
+                        return Err(self.error(path.span, message).into());
+                    }
+                };
+                let mut fields = vec![];
+                for field in declared {
+                    let value = match values
+                        .iter()
+                        .position(|(name, _)| **name == field.name.text)
+                    {
+                        Some(index) => values.swap_remove(index).1,
+                        None => {
+                            let message = format!("missing field `{}`", field.name.text);
+                            return Err(self.error(expr.span, message).into());
+                        }
+                    };
+                    fields.push((field.name.text.clone(), value));
+                }
+                let record = Rc::new(Record { id, name, fields });
+                match self.res(path) {
+                    Res::Type(_) => Value::Record(record),
+                    _ => Value::Variant(record),
+                }
+            }
+            ExprKind::Closure(_) => Value::Closure(Closure {
+                id: expr.id,
+                captures: Captures::Env(env.clone()),
+            }),
+            ExprKind::Call {
+                callee, arguments, ..
+            } => {
+                let selection = self.analysis.typing.overloads().get(expr);
+                if let (ExprKind::Path(_), Some(selection)) = (&callee.kind, selection) {
+                    let arguments = self.arguments(arguments, env)?;
+                    let function = self.functions[&selection.function];
+                    return self.call_function(function, &selection.bindings, arguments, expr.span);
+                }
+                let callee = self.eval(callee, env)?;
+                let arguments = self.arguments(arguments, env)?;
+                self.call(callee, arguments, expr.span)?
+            }
+            ExprKind::MethodCall { .. } => unreachable!("method calls are desugared"),
+            ExprKind::Field { receiver, field } => match self.eval(receiver, env)? {
+                Value::Record(record) | Value::Variant(record) => match record.get(&field.text) {
+                    Some(value) => value.clone(),
+                    None => {
+                        let message = format!("`{}` has no field `{}`", record.name, field.text);
+                        return Err(self.error(field.span, message).into());
+                    }
+                },
+                value => {
+                    let message = format!("`{}` has no field `{}`", value, field.text);
+                    return Err(self.error(field.span, message).into());
+                }
+            },
+            ExprKind::Index { receiver, index } => {
+                let list = self.eval(receiver, env)?;
+                let index_value = self.eval(index, env)?;
+                match (&list, &index_value) {
+                    (Value::List(elements), Value::Int(position)) => {
+                        match position
+                            .to_usize()
+                            .and_then(|position| elements.get(position))
+                        {
+                            Some(element) => element.clone(),
+                            None => {
+                                let message = format!(
+                                    "index {} is out of bounds of a list of length {}",
+                                    position,
+                                    elements.len(),
+                                );
+                                return Err(self.error(index.span, message).into());
+                            }
+                        }
+                    }
+                    (Value::List(_), _) => {
+                        let message = format!("cannot index a list with `{}`", index_value);
+                        return Err(self.error(index.span, message).into());
+                    }
+                    _ => {
+                        let message = format!("cannot index into `{}`", list);
+                        return Err(self.error(receiver.span, message).into());
+                    }
+                }
+            }
+            ExprKind::Block(block) => self.block(block, env)?,
+            ExprKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if self.condition(condition, env)? {
+                    self.block(then_branch, env)?
+                } else if let Some(else_branch) = else_branch {
+                    self.block(else_branch, env)?
+                } else {
+                    Value::Unit
+                }
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                let value = self.eval(scrutinee, env)?;
+                for arm in arms {
+                    let env = Env::child(env);
+                    if !self.matches(&arm.pattern, &value, &env)? {
+                        continue;
+                    }
+                    if let Some(guard) = &arm.guard {
+                        if !self.condition(guard, &env)? {
+                            continue;
+                        }
+                    }
+                    return self.eval(&arm.body, &env);
+                }
+                let message = format!("no arm of this `match` matches `{}`", value);
+                return Err(self.error(scrutinee.span, message).into());
+            }
+            ExprKind::Loop { body, .. } => loop {
+                match self.block(body, env) {
+                    Ok(_) => {}
+                    Err(Unwind::Break(target, value)) if target == expr.id => break value,
+                    Err(Unwind::Continue(target)) if target == expr.id => {}
+                    Err(unwind) => return Err(unwind),
+                }
+            },
+            ExprKind::For {
+                pattern,
+                iterable,
+                body,
+                ..
+            } => {
+                let elements = match self.eval(iterable, env)? {
+                    Value::List(elements) => elements,
+                    value => {
+                        let message = format!("cannot iterate over `{}`", value);
+                        return Err(self.error(iterable.span, message).into());
+                    }
+                };
+                for element in elements.iter() {
+                    let env = Env::child(env);
+                    self.bind(pattern, element, &env)?;
+                    match self.block(body, &env) {
+                        Ok(_) => {}
+                        Err(Unwind::Break(target, _)) if target == expr.id => break,
+                        Err(Unwind::Continue(target)) if target == expr.id => {}
+                        Err(unwind) => return Err(unwind),
+                    }
+                }
+                Value::Unit
+            }
+            ExprKind::Break { target, value } => {
+                let value = self.optional(value.as_deref(), env)?;
+                return Err(Unwind::Break(*target, value));
+            }
+            ExprKind::Continue { target } => return Err(Unwind::Continue(*target)),
+            ExprKind::Return { target, value } => {
+                let value = self.optional(value.as_deref(), env)?;
+                let call = env
+                    .call_of(*target)
+                    .expect("return target is not an enclosing function");
+                // A closure may outlive the call it was created in.
+                if call != 0 && self.calls.binary_search(&call).is_err() {
+                    let message = "cannot return from a function that has already returned";
+                    return Err(self.error(expr.span, message).into());
+                }
+                return Err(Unwind::Return(call, value));
+            }
+        })
+    }
+
+    fn all(&mut self, exprs: &'h [Expr], env: &Rc<Env>) -> Eval<Vec<Value>> {
+        exprs.iter().map(|expr| self.eval(expr, env)).collect()
+    }
+
+    fn arguments(&mut self, arguments: &'h [Argument], env: &Rc<Env>) -> Eval<Vec<Value>> {
+        arguments
+            .iter()
+            .map(|argument| self.eval(&argument.value, env))
+            .collect()
+    }
+
+    fn optional(&mut self, expr: Option<&'h Expr>, env: &Rc<Env>) -> Eval {
+        match expr {
+            Some(expr) => self.eval(expr, env),
+            None => Ok(Value::Unit),
+        }
+    }
+
+    fn condition(&mut self, condition: &'h Expr, env: &Rc<Env>) -> Eval<bool> {
+        match self.eval(condition, env)? {
+            Value::Bool(value) => Ok(value),
+            value => {
+                let message = format!("expected a `Bool`, found `{}`", value);
+                Err(self.error(condition.span, message).into())
+            }
+        }
+    }
+
+    /// The value a path used as an expression refers to.
+    fn path(&self, path: &Path, env: &Rc<Env>) -> Result<Value, RuntimeError> {
+        Ok(match self.res(path) {
+            Res::Local(binding) => match env.get(*binding) {
+                Some(value) => value,
+                None => {
+                    let message = format!("`{}` is used before it is bound", path);
+                    return Err(self.error(path.span, message));
+                }
+            },
+            Res::Functions(overloads) if overloads.len() == 1 => {
+                let function = self.functions[&overloads[0]];
+                Value::Function(function.id, function.name.text.as_str().into())
+            }
+            &Res::Variant(id) => {
+                let (r#enum, variant) = self.variants[&id];
+                let name = format!("{}::{}", r#enum.name.text, variant.name.text);
+                if variant.fields.is_empty() {
+                    Value::Variant(Rc::new(Record {
+                        id,
+                        name,
+                        fields: vec![],
+                    }))
+                } else {
+                    Value::Constructor(id, name.into())
+                }
+            }
+            _ => {
+                let message = format!("`{}` is not a value", path);
+                return Err(self.error(path.span, message));
+            }
+        })
+    }
+
+    // Calls
+
+    /// Call a value with positional arguments.
+    fn call(&mut self, callee: Value, mut arguments: Vec<Value>, span: Span) -> Eval {
+        match callee {
+            Value::Function(id, _) => {
+                let function = self.functions[&id];
+                let parameters = &function.parameters;
+                if arguments.len() > parameters.len() {
+                    let message = format!(
+                        "`{}` takes at most {} arguments, but {} were given",
+                        function.name.text,
+                        parameters.len(),
+                        arguments.len(),
+                    );
+                    return Err(self.error(span, message).into());
+                }
+                let mut bindings = vec![];
+                for (index, parameter) in parameters.iter().enumerate() {
+                    if index < arguments.len() {
+                        bindings.push(ParameterBinding::Argument(index));
+                    } else if parameter.default.is_some() {
+                        bindings.push(ParameterBinding::Default);
+                    } else {
+                        let message = match &parameter.label {
+                            Some(label) => format!(
+                                "missing an argument for `{}` of `{}`",
+                                label.text, function.name.text,
+                            ),
+                            None => format!(
+                                "missing an argument for parameter {} of `{}`",
+                                index + 1,
+                                function.name.text,
+                            ),
+                        };
+                        return Err(self.error(span, message).into());
+                    }
+                }
+                self.call_function(function, &bindings, arguments, span)
+            }
+            Value::Constructor(id, name) => {
+                let (_, variant) = self.variants[&id];
+                if arguments.len() != variant.fields.len() {
+                    let message = format!(
+                        "`{}` has {} fields, but {} were given",
+                        name,
+                        variant.fields.len(),
+                        arguments.len(),
+                    );
+                    return Err(self.error(span, message).into());
+                }
+                let fields = variant
+                    .fields
+                    .iter()
+                    .map(|field| field.name.text.clone())
+                    .zip(arguments.drain(..))
+                    .collect();
+                Ok(Value::Variant(Rc::new(Record {
+                    id,
+                    name: name.to_string(),
+                    fields,
+                })))
+            }
+            Value::Closure(closure) => self.call_closure(&closure, arguments, span),
+            value => {
+                let message = format!("`{}` is not a function", value);
+                Err(self.error(span, message).into())
+            }
+        }
+    }
+
+    /// Enter a call, unless too many are already in progress.
+    ///
+    /// Returns the number of the call.
+    fn enter(&mut self, name: String, call: Span) -> Result<u64, RuntimeError> {
+        if self.stack.len() >= MAX_DEPTH {
+            let message = format!("stack overflow: more than {} calls in progress", MAX_DEPTH);
+            return Err(self.error(call, message));
+        }
+        let number = self.next_call;
+        self.next_call += 1;
+        self.stack.push(Frame { name, call });
+        self.calls.push(number);
+        Ok(number)
+    }
+
+    /// Exit the innermost call.
+    fn exit(&mut self) {
+        self.stack.pop();
+        self.calls.pop();
+    }
+
+    /// Call a function declaration, binding its parameters as the call binds them.
+    ///
+    /// A native function is called with the values of its parameters.
+    fn call_function(
+        &mut self,
+        function: &'h Function,
+        bindings: &[ParameterBinding],
+        mut arguments: Vec<Value>,
+        span: Span,
+    ) -> Eval {
+        if let Some(&native) = self.natives.get(&function.id) {
+            let values: Vec<_> = bindings
+                .iter()
+                .map(|binding| match *binding {
+                    ParameterBinding::Argument(index) => {
+                        mem::replace(&mut arguments[index], Value::Unit)
+                    }
+                    _ => unreachable!("native function with a default"),
+                })
+                .collect();
+            // A native call counts toward the depth, as it does in the virtual machine.
+            self.enter(function.name.text.clone(), span)?;
+            let result = native(&values);
+            self.exit();
+            return result.map_err(|message| self.error(span, message).into());
+        }
+        let call = self.enter(function.name.text.clone(), span)?;
+        let env = Env::call(function.id, call, None);
+        let mut body = || {
+            for (parameter, binding) in function.parameters.iter().zip(bindings) {
+                let value = match (binding, &parameter.default) {
+                    (&ParameterBinding::Argument(index), _) => {
+                        mem::replace(&mut arguments[index], Value::Unit)
+                    }
+                    (ParameterBinding::Default, Some(default)) => self.eval(default, &env)?,
+                    _ => unreachable!("call not desugared"),
+                };
+                self.bind(&parameter.pattern, &value, &env)?;
+            }
+            match self.block(&function.body, &env) {
+                Err(Unwind::Return(target, value)) if target == call => Ok(value),
+                result => result,
+            }
+        };
+        let result = body();
+        self.exit();
+        result
+    }
+
+    fn call_closure(&mut self, closure: &Closure, arguments: Vec<Value>, span: Span) -> Eval {
+        let expr = self.closures[&closure.id];
+        if arguments.len() != expr.parameters.len() {
+            let message = format!(
+                "this closure takes {} arguments, but {} were given",
+                expr.parameters.len(),
+                arguments.len(),
+            );
+            return Err(self.error(span, message).into());
+        }
+        let name = match &expr.label {
+            Some(label) => label.text.clone(),
+            None => "closure".to_string(),
+        };
+        let call = self.enter(name, span)?;
+        let env = match &closure.captures {
+            Captures::Env(env) => Env::call(closure.id, call, Some(env)),
+            Captures::Upvalues(..) => {
+                unreachable!("compiled closure in the tree-walking interpreter")
+            }
+        };
+        let mut body = || {
+            for (parameter, value) in expr.parameters.iter().zip(&arguments) {
+                self.bind(&parameter.pattern, value, &env)?;
+            }
+            match self.block(&expr.body, &env) {
+                Err(Unwind::Return(target, value)) if target == call => Ok(value),
+                result => result,
+            }
+        };
+        let result = body();
+        self.exit();
+        result
+    }
+
+    // Patterns
+
+    /// Bind the names of an irrefutable pattern, such as that of a parameter.
+    fn bind(&self, pattern: &Pattern, value: &Value, env: &Env) -> Result<(), RuntimeError> {
+        if self.matches(pattern, value, env)? {
+            Ok(())
+        } else {
+            let message = format!("`{}` does not match this pattern", value);
+            Err(self.error(pattern.span, message))
+        }
+    }
+
+    /// Whether the value matches the pattern, binding its names if so.
+    fn matches(&self, pattern: &Pattern, value: &Value, env: &Env) -> Result<bool, RuntimeError> {
+        Ok(match (&pattern.kind, value) {
+            (PatternKind::Wildcard, _) => true,
+            (PatternKind::Binding(_), _) => {
+                env.bind(pattern.id, value.clone());
+                true
+            }
+            (PatternKind::Literal(literal), _) => literal_value(literal) == *value,
+            (PatternKind::Tuple(patterns), Value::Tuple(values)) => {
+                patterns.len() == values.len() && self.all_match(patterns, values.iter(), env)?
+            }
+            (PatternKind::Constructor { path, fields }, Value::Variant(variant)) => {
+                *self.res(path) == Res::Variant(variant.id)
+                    && fields.len() == variant.fields.len()
+                    && self.all_match(fields, variant.fields.iter().map(|(_, v)| v), env)?
+            }
+            (PatternKind::Record { path, fields }, Value::Record(record))
+            | (PatternKind::Record { path, fields }, Value::Variant(record)) => {
+                let id = match *self.res(path) {
+                    Res::Type(id) | Res::Variant(id) => id,
+                    _ => return Ok(false),
+                };
+                if id != record.id {
+                    return Ok(false);
+                }
+                for field in fields {
+                    match record.get(&field.name.text) {
+                        Some(value) if self.matches(&field.pattern, value, env)? => {}
+                        _ => return Ok(false),
+                    }
+                }
+                true
+            }
+            _ => false,
+        })
+    }
+
+    fn all_match<'v>(
+        &self,
+        patterns: &[Pattern],
+        values: impl Iterator<Item = &'v Value>,
+        env: &Env,
+    ) -> Result<bool, RuntimeError> {
+        for (pattern, value) in patterns.iter().zip(values) {
+            if !self.matches(pattern, value, env)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
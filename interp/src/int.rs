@@ -0,0 +1,263 @@
+//! Integers of any size.
+
+use num_bigint::{BigInt, ParseBigIntError};
+use num_traits::ToPrimitive;
+use std::{cmp::Ordering, convert::TryFrom, fmt, ops, rc::Rc, str::FromStr};
+
+/// An `Int`, which may be of any size.
+///
+/// Integers that fit in an `i64` are stored as one, and arithmetic on them takes a fast path.
+/// Results that overflow it are promoted to a bignum, and bignum results that fit in it are
+/// demoted again, so each integer has exactly one representation.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Int(Repr);
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+enum Repr {
+    Small(i64),
+    /// Never fits in an `i64`.
+    Big(Rc<BigInt>),
+}
+
+impl Int {
+    fn big(value: BigInt) -> Int {
+        match value.to_i64() {
+            Some(value) => Int(Repr::Small(value)),
+            None => Int(Repr::Big(Rc::new(value))),
+        }
+    }
+
+    fn to_big(&self) -> BigInt {
+        match &self.0 {
+            Repr::Small(value) => BigInt::from(*value),
+            Repr::Big(value) => BigInt::clone(value),
+        }
+    }
+
+    /// The integer as an `i64`, if it fits in one.
+    pub fn to_i64(&self) -> Option<i64> {
+        match self.0 {
+            Repr::Small(value) => Some(value),
+            Repr::Big(_) => None,
+        }
+    }
+
+    /// The integer as a `usize`, if it fits in one, such as to index a list.
+    pub fn to_usize(&self) -> Option<usize> {
+        usize::try_from(self.to_i64()?).ok()
+    }
+
+    /// Whether the integer is zero.
+    pub fn is_zero(&self) -> bool {
+        self.0 == Repr::Small(0)
+    }
+
+    /// The quotient, rounded toward zero, or `None` if dividing by zero.
+    pub fn checked_div(&self, divisor: &Int) -> Option<Int> {
+        if divisor.is_zero() {
+            return None;
+        }
+        Some(match (&self.0, &divisor.0) {
+            (&Repr::Small(a), &Repr::Small(b)) => match a.checked_div(b) {
+                Some(value) => Int::from(value),
+                None => Int::big(BigInt::from(a) / b),
+            },
+            _ => Int::big(self.to_big() / divisor.to_big()),
+        })
+    }
+
+    /// The remainder of the quotient rounded toward zero, with the sign of `self`,
+    /// or `None` if dividing by zero.
+    pub fn checked_rem(&self, divisor: &Int) -> Option<Int> {
+        if divisor.is_zero() {
+            return None;
+        }
+        Some(match (&self.0, &divisor.0) {
+            (&Repr::Small(a), &Repr::Small(b)) => Int::from(a.checked_rem(b).unwrap_or(0)),
+            _ => Int::big(self.to_big() % divisor.to_big()),
+        })
+    }
+}
+
+impl From<i64> for Int {
+    fn from(value: i64) -> Self {
+        Int(Repr::Small(value))
+    }
+}
+
+impl From<usize> for Int {
+    fn from(value: usize) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Int::from(value),
+            Err(_) => Int::big(BigInt::from(value)),
+        }
+    }
+}
+
+impl FromStr for Int {
+    type Err = ParseBigIntError;
+
+    /// Parses decimal digits, as in an integer literal.
+    fn from_str(digits: &str) -> Result<Self, Self::Err> {
+        match digits.parse() {
+            Ok(value) => Ok(Int(Repr::Small(value))),
+            Err(_) => digits.parse().map(Int::big),
+        }
+    }
+}
+
+macro_rules! arithmetic {
+    ($Trait:ident, $method:ident, $checked:ident) => {
+        impl<'a> ops::$Trait<&'a Int> for &'a Int {
+            type Output = Int;
+
+            fn $method(self, other: &Int) -> Int {
+                match (&self.0, &other.0) {
+                    (&Repr::Small(a), &Repr::Small(b)) => match a.$checked(b) {
+                        Some(value) => Int::from(value),
+                        None => Int::big(ops::$Trait::$method(BigInt::from(a), b)),
+                    },
+                    _ => Int::big(ops::$Trait::$method(self.to_big(), other.to_big())),
+                }
+            }
+        }
+
+        impl ops::$Trait for Int {
+            type Output = Int;
+
+            fn $method(self, other: Int) -> Int {
+                ops::$Trait::$method(&self, &other)
+            }
+        }
+    };
+}
+
+arithmetic!(Add, add, checked_add);
+arithmetic!(Sub, sub, checked_sub);
+arithmetic!(Mul, mul, checked_mul);
+
+impl ops::Neg for &Int {
+    type Output = Int;
+
+    fn neg(self) -> Int {
+        match self.0 {
+            Repr::Small(value) => match value.checked_neg() {
+                Some(value) => Int::from(value),
+                None => Int::big(-BigInt::from(value)),
+            },
+            Repr::Big(ref value) => Int::big(-BigInt::clone(value)),
+        }
+    }
+}
+
+impl ops::Neg for Int {
+    type Output = Int;
+
+    fn neg(self) -> Int {
+        -&self
+    }
+}
+
+impl Ord for Int {
+    fn cmp(&self, other: &Int) -> Ordering {
+        match (&self.0, &other.0) {
+            (Repr::Small(a), Repr::Small(b)) => a.cmp(b),
+            _ => self.to_big().cmp(&other.to_big()),
+        }
+    }
+}
+
+impl PartialOrd for Int {
+    fn partial_cmp(&self, other: &Int) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Int {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Repr::Small(value) => fmt::Display::fmt(value, f),
+            Repr::Big(value) => fmt::Display::fmt(value, f),
+        }
+    }
+}
+
+impl fmt::Debug for Int {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(digits: &str) -> Int {
+        digits.parse().unwrap()
+    }
+
+    /// `i64::MAX + 1`, the smallest integer that does not fit in an `i64`.
+    const OVER_MAX: &str = "9223372036854775808";
+
+    #[test]
+    fn overflowing_division_is_promoted() {
+        let quotient = Int::from(i64::MIN).checked_div(&Int::from(-1_i64)).unwrap();
+        assert_eq!(quotient, int(OVER_MAX));
+        assert_eq!(quotient.to_i64(), None);
+    }
+
+    #[test]
+    fn overflowing_remainder_is_zero() {
+        let remainder = Int::from(i64::MIN).checked_rem(&Int::from(-1_i64)).unwrap();
+        assert_eq!(remainder.to_i64(), Some(0));
+    }
+
+    #[test]
+    fn overflowing_negation_is_promoted() {
+        let negated = -Int::from(i64::MIN);
+        assert_eq!(negated, int(OVER_MAX));
+        assert_eq!(negated.to_i64(), None);
+        assert_eq!((-negated).to_i64(), Some(i64::MIN));
+    }
+
+    #[test]
+    fn overflowing_arithmetic_is_promoted() {
+        assert_eq!(Int::from(i64::MAX) + Int::from(1_i64), int(OVER_MAX));
+        assert_eq!(
+            Int::from(i64::MIN) - Int::from(1_i64),
+            int("-9223372036854775809"),
+        );
+        assert_eq!(
+            Int::from(i64::MAX) * Int::from(2_i64),
+            int("18446744073709551614"),
+        );
+    }
+
+    #[test]
+    fn big_results_that_fit_are_demoted() {
+        let big = int(OVER_MAX);
+        assert_eq!((&big - &Int::from(1_i64)).to_i64(), Some(i64::MAX));
+        assert_eq!((&big - &big).to_i64(), Some(0));
+        assert_eq!((-&big).to_i64(), Some(i64::MIN));
+        assert_eq!(big.checked_div(&big).and_then(|q| q.to_i64()), Some(1));
+        assert_eq!(
+            big.checked_rem(&Int::from(10_i64)).and_then(|r| r.to_i64()),
+            Some(8)
+        );
+        // Equal values have one representation, so they compare and hash alike.
+        assert_eq!(&big - &Int::from(1_i64), Int::from(i64::MAX));
+    }
+
+    #[test]
+    fn literals_are_stored_by_size() {
+        assert_eq!(int("9223372036854775807").to_i64(), Some(i64::MAX));
+        assert_eq!(int(OVER_MAX).to_i64(), None);
+        assert_eq!(int(OVER_MAX).to_string(), OVER_MAX);
+    }
+
+    #[test]
+    fn dividing_by_zero_fails() {
+        assert_eq!(Int::from(1_i64).checked_div(&Int::from(0_i64)), None);
+        assert_eq!(int(OVER_MAX).checked_rem(&Int::from(0_i64)), None);
+    }
+}
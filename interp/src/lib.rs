@@ -0,0 +1,171 @@
+//! # A tree-walking interpreter for the Nafi programming language.
+//!
+//! Programs are run from their [analyzed](nafi_hir::analyze) HIR, by evaluating each
+//! expression in turn as the tree is walked:
+//!
+//! - Function expressions evaluate to closures, which capture the bindings in scope.
+//! - Calls bind their arguments to parameters as overload resolution selected,
+//!   evaluating the defaults of parameters that are not given.
+//!   Named arguments and trailing closures are desugared into positional ones before this.
+//! - `break`, `continue`, and `return` unwind evaluation to the node they exit.
+//! - The native functions of [the prelude](nafi_hir::prelude) are built in, and called
+//!   with the values of their parameters.
+//!
+//! A program that fails at runtime stops with a [`RuntimeError`], which records the calls
+//! that were in progress.
+//!
+//! Programs that loop heavily run faster when [compiled](compile) to [bytecode] and
+//! [executed](execute) by a stack machine, which computes the same values.
+
+#![warn(missing_docs)]
+
+pub use crate::{
+    error::{Frame, RuntimeError},
+    int::Int,
+    value::{Closure, Record, Value},
+};
+use nafi_hir::{diagnostics::Diagnostic, lower, Analysis, SourceId};
+use nafi_parser::Project;
+use std::fmt;
+
+pub mod bytecode;
+mod compile;
+mod error;
+mod eval;
+mod int;
+mod prelude;
+mod value;
+mod vm;
+
+/// How many calls may be in progress at once before a program is stopped, by either engine.
+const MAX_DEPTH: usize = 10_000;
+
+/// Run the main function of an analyzed program, and return its value.
+///
+/// The analysis must not have reported any errors.
+///
+/// This may be called on a thread with a small stack, as evaluation grows its own stack
+/// as it needs to.
+pub fn run(analysis: &Analysis) -> Result<Value, RuntimeError> {
+    eval::Interpreter::new(analysis).run()
+}
+
+/// Compile an analyzed program to bytecode.
+///
+/// The analysis must not have reported any errors.
+pub fn compile(analysis: &Analysis) -> bytecode::Code {
+    compile::Compiler::new(analysis).compile()
+}
+
+/// Execute the main function of a compiled program, and return its value.
+pub fn execute(code: &bytecode::Code) -> Result<Value, RuntimeError> {
+    vm::Machine::new(code).run()
+}
+
+/// Why a program could not be evaluated.
+#[derive(Debug)]
+pub enum Error {
+    /// The source could not be parsed.
+    Parse(Box<dyn std::error::Error>),
+    /// The program has errors, reported alongside any warnings.
+    Compile(Vec<Diagnostic>),
+    /// The program stopped with an error while running.
+    Runtime(RuntimeError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(error) => write!(f, "{}", error),
+            Error::Compile(diagnostics) => {
+                let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+                write!(
+                    f,
+                    "the program has {} error{}",
+                    errors,
+                    if errors == 1 { "" } else { "s" },
+                )
+            }
+            Error::Runtime(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// How to run an analyzed program. Both compute the same values and raise the same errors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Engine {
+    /// Walk its tree, with [`run`].
+    Walk,
+    /// Compile it to bytecode and execute that, with [`compile`] and [`execute`].
+    Bytecode,
+}
+
+impl Engine {
+    /// Run the main function of an analyzed program, and return its value.
+    ///
+    /// The analysis must not have reported any errors.
+    pub fn run(self, analysis: &Analysis) -> Result<Value, RuntimeError> {
+        match self {
+            Engine::Walk => run(analysis),
+            Engine::Bytecode => execute(&compile(analysis)),
+        }
+    }
+}
+
+/// An analyzed program, along with the warnings reported about it.
+pub type Analyzed = (Analysis, Vec<Diagnostic>);
+
+fn analyze(
+    (program, mut diagnostics): (nafi_hir::items::Program, Vec<Diagnostic>),
+) -> Result<Analyzed, Error> {
+    let (analysis, analysis_diagnostics) = nafi_hir::analyze(program);
+    diagnostics.extend(analysis_diagnostics);
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        return Err(Error::Compile(diagnostics));
+    }
+    Ok((analysis, diagnostics))
+}
+
+/// Analyze a script, the body of a function expression such as one entered in a REPL.
+///
+/// Spans in diagnostics refer to the script as source `0`.
+pub fn analyze_script(source: &str) -> Result<Analyzed, Error> {
+    let function = nafi_parser::parse(source).map_err(Error::Parse)?;
+    analyze(lower::lower_script(SourceId(0), &function))
+}
+
+/// Analyze a project, whose main function runs the statements of its root file.
+///
+/// Spans in diagnostics refer to each file by its index in [`Project::files`].
+pub fn analyze_project(project: &Project<'_>) -> Result<Analyzed, Error> {
+    let sources: Vec<_> = project
+        .files()
+        .iter()
+        .zip(project.trees())
+        .enumerate()
+        .map(|(index, (source, file))| lower::ModuleSource {
+            source: SourceId(index as u32),
+            module_path: source.module_path(),
+            file,
+        })
+        .collect();
+    analyze(lower::lower_program(&sources))
+}
+
+/// Evaluate a script with an engine, as [analyzed](analyze_script).
+///
+/// Spans in errors refer to the script as source `0`.
+pub fn eval_script(source: &str, engine: Engine) -> Result<Value, Error> {
+    let (analysis, _) = analyze_script(source)?;
+    engine.run(&analysis).map_err(Error::Runtime)
+}
+
+/// Evaluate a project with an engine, as [analyzed](analyze_project).
+///
+/// Spans in errors refer to each file by its index in [`Project::files`].
+pub fn eval_project(project: &Project<'_>, engine: Engine) -> Result<Value, Error> {
+    let (analysis, _) = analyze_project(project)?;
+    engine.run(&analysis).map_err(Error::Runtime)
+}
@@ -0,0 +1,101 @@
+use nafi_interp::{Analyzed, Engine, Value};
+use std::{
+    env,
+    error::Error,
+    io::{self, prelude::*},
+    process,
+};
+
+/// How to run a program.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Mode {
+    /// With an engine.
+    Run(Engine),
+    /// Print the bytecode it compiles to, without running it.
+    Disassemble,
+}
+
+/// Run the analyzed program, reporting any diagnostics.
+fn run(
+    analyzed: Result<Analyzed, nafi_interp::Error>,
+    files: &[(&str, &str)],
+    mode: Mode,
+) -> Result<(), Box<dyn Error>> {
+    let analysis = match analyzed {
+        Ok((analysis, warnings)) => {
+            for warning in &warnings {
+                eprintln!("{}", warning.render(files));
+            }
+            analysis
+        }
+        Err(nafi_interp::Error::Compile(diagnostics)) => {
+            for diagnostic in &diagnostics {
+                eprintln!("{}", diagnostic.render(files));
+            }
+            return Err("the program has errors, so it was not run".into());
+        }
+        Err(error) => return Err(error.into()),
+    };
+    let result = match mode {
+        Mode::Run(engine) => engine.run(&analysis),
+        Mode::Disassemble => {
+            print!("{}", nafi_interp::compile(&analysis));
+            return Ok(());
+        }
+    };
+    match result {
+        Ok(Value::Unit) => Ok(()),
+        Ok(value) => {
+            println!("{}", value);
+            Ok(())
+        }
+        Err(error) => {
+            eprintln!("{}", error.render(files));
+            Err("the program stopped with an error".into())
+        }
+    }
+}
+
+/// Run the project rooted at `path`.
+fn load(path: &str, mode: Mode) -> Result<(), Box<dyn Error>> {
+    let sources = nafi_parser::Sources::default();
+    let project = nafi_parser::Project::load(path, &sources)?;
+    let names: Vec<_> = project
+        .files()
+        .iter()
+        .map(|file| (file.path().to_str().unwrap_or("<file>"), file.source()))
+        .collect();
+    run(nafi_interp::analyze_project(&project), &names, mode)
+}
+
+/// Run the script read from standard input.
+fn script(mode: Mode) -> Result<(), Box<dyn Error>> {
+    let mut source = String::new();
+    io::stdin().read_to_string(&mut source)?;
+    run(
+        nafi_interp::analyze_script(&source),
+        &[("<stdin>", &source)],
+        mode,
+    )
+}
+
+/// Usage: `nafi-interp [--bytecode | --disassemble] [path]`
+fn main() {
+    let mut mode = Mode::Run(Engine::Walk);
+    let mut path = None;
+    for argument in env::args().skip(1) {
+        match argument.as_str() {
+            "--bytecode" => mode = Mode::Run(Engine::Bytecode),
+            "--disassemble" => mode = Mode::Disassemble,
+            _ => path = Some(argument),
+        }
+    }
+    let result = match path {
+        Some(path) => load(&path, mode),
+        None => script(mode),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,223 @@
+//! The native functions of the prelude, which are built into the interpreter.
+//!
+//! Each is looked up by the name of its declaration in the prelude. The overloads of a name
+//! share one native function, which tells them apart by the values of their arguments.
+
+use crate::{int::Int, value::Value};
+use std::rc::Rc;
+
+/// A native function, called with the values of the parameters of its declaration.
+///
+/// An error is raised where the function was called.
+pub(crate) type Native = fn(&[Value]) -> Result<Value, String>;
+
+/// The native function of a name, if there is one.
+pub(crate) fn native(name: &str) -> Option<Native> {
+    Some(match name {
+        "print" => print,
+        "assert" => assert,
+        "assertEqual" => assert_equal,
+        "add" => add,
+        "sub" => sub,
+        "mul" => mul,
+        "div" => div,
+        "rem" => rem,
+        "neg" => neg,
+        "eq" => eq,
+        "lt" => lt,
+        "length" => length,
+        "push" => push,
+        "concat" => concat,
+        "slice" => slice,
+        "reverse" => reverse,
+        "range" => range,
+        "string" => string,
+        "chars" => chars,
+        "fromChars" => from_chars,
+        _ => return None,
+    })
+}
+
+/// The text of a value, as `print` prints it.
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.to_string(),
+        value => value.to_string(),
+    }
+}
+
+fn print(arguments: &[Value]) -> Result<Value, String> {
+    println!("{}", text(&arguments[0]));
+    Ok(Value::Unit)
+}
+
+fn assert(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [Value::Bool(true)] => Ok(Value::Unit),
+        _ => Err("assertion failed".to_string()),
+    }
+}
+
+fn assert_equal(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [actual, expected] if actual == expected => Ok(Value::Unit),
+        [actual, expected] => Err(format!(
+            "assertion failed: `{}` is not equal to `{}`",
+            actual, expected,
+        )),
+        _ => unreachable!("ill-typed call of `assertEqual`"),
+    }
+}
+
+fn integers(arguments: &[Value]) -> (&Int, &Int) {
+    match arguments {
+        [Value::Int(a), Value::Int(b)] => (a, b),
+        _ => unreachable!("ill-typed call of an arithmetic function"),
+    }
+}
+
+fn add(arguments: &[Value]) -> Result<Value, String> {
+    let (a, b) = integers(arguments);
+    Ok(Value::Int(a + b))
+}
+
+fn sub(arguments: &[Value]) -> Result<Value, String> {
+    let (a, b) = integers(arguments);
+    Ok(Value::Int(a - b))
+}
+
+fn mul(arguments: &[Value]) -> Result<Value, String> {
+    let (a, b) = integers(arguments);
+    Ok(Value::Int(a * b))
+}
+
+fn div(arguments: &[Value]) -> Result<Value, String> {
+    let (a, b) = integers(arguments);
+    a.checked_div(b)
+        .map(Value::Int)
+        .ok_or_else(|| "division by zero".to_string())
+}
+
+fn rem(arguments: &[Value]) -> Result<Value, String> {
+    let (a, b) = integers(arguments);
+    a.checked_rem(b)
+        .map(Value::Int)
+        .ok_or_else(|| "division by zero".to_string())
+}
+
+fn neg(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [Value::Int(a)] => Ok(Value::Int(-a)),
+        _ => unreachable!("ill-typed call of `neg`"),
+    }
+}
+
+fn eq(arguments: &[Value]) -> Result<Value, String> {
+    Ok(Value::Bool(arguments[0] == arguments[1]))
+}
+
+fn lt(arguments: &[Value]) -> Result<Value, String> {
+    let (a, b) = integers(arguments);
+    Ok(Value::Bool(a < b))
+}
+
+fn length(arguments: &[Value]) -> Result<Value, String> {
+    Ok(Value::Int(match &arguments[0] {
+        Value::List(elements) => Int::from(elements.len()),
+        Value::String(text) => Int::from(text.chars().count()),
+        _ => unreachable!("ill-typed call of `length`"),
+    }))
+}
+
+fn push(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [Value::List(elements), element] => {
+            let mut elements = Vec::clone(elements);
+            elements.push(element.clone());
+            Ok(Value::List(Rc::new(elements)))
+        }
+        _ => unreachable!("ill-typed call of `push`"),
+    }
+}
+
+fn concat(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [Value::List(first), Value::List(second)] => {
+            let elements = first.iter().chain(second.iter()).cloned().collect();
+            Ok(Value::List(Rc::new(elements)))
+        }
+        [Value::String(first), Value::String(second)] => {
+            Ok(Value::String(format!("{}{}", first, second).into()))
+        }
+        _ => unreachable!("ill-typed call of `concat`"),
+    }
+}
+
+fn slice(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [Value::List(elements), Value::Int(start), Value::Int(end)] => {
+            match (start.to_usize(), end.to_usize()) {
+                (Some(start), Some(end)) if start <= end && end <= elements.len() => {
+                    Ok(Value::List(Rc::new(elements[start..end].to_vec())))
+                }
+                _ => Err(format!(
+                    "cannot slice from {} to {} of a list of length {}",
+                    start,
+                    end,
+                    elements.len(),
+                )),
+            }
+        }
+        _ => unreachable!("ill-typed call of `slice`"),
+    }
+}
+
+fn reverse(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [Value::List(elements)] => Ok(Value::List(Rc::new(
+            elements.iter().rev().cloned().collect(),
+        ))),
+        _ => unreachable!("ill-typed call of `reverse`"),
+    }
+}
+
+fn range(arguments: &[Value]) -> Result<Value, String> {
+    let (start, end) = integers(arguments);
+    let mut elements = vec![];
+    let mut next = start.clone();
+    while next < *end {
+        let after = &next + &Int::from(1_i64);
+        elements.push(Value::Int(next));
+        next = after;
+    }
+    Ok(Value::List(Rc::new(elements)))
+}
+
+fn string(arguments: &[Value]) -> Result<Value, String> {
+    Ok(Value::String(text(&arguments[0]).into()))
+}
+
+fn chars(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [Value::String(text)] => Ok(Value::List(Rc::new(
+            text.chars().map(Value::Char).collect(),
+        ))),
+        _ => unreachable!("ill-typed call of `chars`"),
+    }
+}
+
+fn from_chars(arguments: &[Value]) -> Result<Value, String> {
+    match arguments {
+        [Value::List(chars)] => Ok(Value::String(
+            chars
+                .iter()
+                .map(|value| match value {
+                    Value::Char(char) => *char,
+                    _ => unreachable!("ill-typed call of `fromChars`"),
+                })
+                .collect::<String>()
+                .into(),
+        )),
+        _ => unreachable!("ill-typed call of `fromChars`"),
+    }
+}
@@ -0,0 +1,231 @@
+//! The values programs compute.
+
+use crate::int::Int;
+use nafi_hir::HirId;
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+/// A value.
+///
+/// Values are immutable, so compound values share their parts rather than copying them.
+#[derive(Clone, Debug)]
+pub enum Value {
+    /// An `Int`.
+    Int(Int),
+    /// A `Bool`.
+    Bool(bool),
+    /// A `Char`.
+    Char(char),
+    /// A `String`.
+    String(Rc<str>),
+    /// `()`.
+    Unit,
+    /// A tuple.
+    Tuple(Rc<Vec<Value>>),
+    /// A `List`.
+    List(Rc<Vec<Value>>),
+    /// A value of a record type.
+    Record(Rc<Record>),
+    /// A value of an enum, by its variant.
+    Variant(Rc<Record>),
+    /// A function declaration, by its id and its name.
+    Function(HirId, Rc<str>),
+    /// The constructor of an enum variant with fields, by the id and name of the variant.
+    Constructor(HirId, Rc<str>),
+    /// A closure.
+    Closure(Closure),
+}
+
+/// The fields of a record type or enum variant value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    /// The id of the record type or enum variant.
+    pub id: HirId,
+    /// The name of the record type, or of the variant as `Enum::Variant`.
+    pub name: String,
+    /// The fields, by their names, in the order they are declared.
+    pub fields: Vec<(String, Value)>,
+}
+
+impl Record {
+    /// The value of a field.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// A function expression, with the bindings it captures.
+#[derive(Clone)]
+pub struct Closure {
+    pub(crate) id: HirId,
+    pub(crate) captures: Captures,
+}
+
+/// The bindings a closure captures, as each way of running a program stores them.
+#[derive(Clone)]
+pub(crate) enum Captures {
+    /// The environment a closure was created in, by the tree-walking interpreter.
+    Env(Rc<Env>),
+    /// The values of the closure's upvalues, and the numbers of the calls of the functions
+    /// enclosing it, outermost first, by the virtual machine.
+    ///
+    /// Bindings are immutable, so a closure captures their values rather than the bindings.
+    /// A `return` from an enclosing function exits the call it was created in.
+    Upvalues(Rc<[Value]>, Rc<[u64]>),
+}
+
+impl Captures {
+    fn ptr_eq(&self, other: &Captures) -> bool {
+        match (self, other) {
+            (Captures::Env(a), Captures::Env(b)) => Rc::ptr_eq(a, b),
+            (Captures::Upvalues(a, _), Captures::Upvalues(b, _)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for Closure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Closure").field(&self.id).finish()
+    }
+}
+
+/// The values of local bindings, by the ids of the patterns that bind them.
+///
+/// Each call, `match` arm, and iteration of a `for` loop binds its names in a new
+/// environment, so a closure captures the bindings of exactly the one that created it.
+#[derive(Debug)]
+pub(crate) struct Env {
+    bindings: RefCell<HashMap<HirId, Value>>,
+    /// The call whose body this is the environment of, if it is one,
+    /// by the id of the called function and the number of the call.
+    call: Option<(HirId, u64)>,
+    parent: Option<Rc<Env>>,
+}
+
+impl Env {
+    /// The environment of the body of a call, nested in the environment a closure captured.
+    pub(crate) fn call(function: HirId, call: u64, parent: Option<&Rc<Env>>) -> Rc<Env> {
+        Rc::new(Env {
+            bindings: RefCell::default(),
+            call: Some((function, call)),
+            parent: parent.cloned(),
+        })
+    }
+
+    pub(crate) fn child(parent: &Rc<Env>) -> Rc<Env> {
+        Rc::new(Env {
+            bindings: RefCell::default(),
+            call: None,
+            parent: Some(parent.clone()),
+        })
+    }
+
+    pub(crate) fn get(&self, binding: HirId) -> Option<Value> {
+        match self.bindings.borrow().get(&binding) {
+            Some(value) => Some(value.clone()),
+            None => self.parent.as_ref()?.get(binding),
+        }
+    }
+
+    pub(crate) fn bind(&self, binding: HirId, value: Value) {
+        self.bindings.borrow_mut().insert(binding, value);
+    }
+
+    /// The number of the innermost call of a function enclosing this environment,
+    /// which is the call a `return` from that function exits.
+    pub(crate) fn call_of(&self, function: HirId) -> Option<u64> {
+        match self.call {
+            Some((id, call)) if id == function => Some(call),
+            _ => self.parent.as_ref()?.call_of(function),
+        }
+    }
+}
+
+impl PartialEq for Value {
+    /// Compares values structurally. Functions are only equal to themselves,
+    /// and closures only to the closure created by the same evaluation.
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Char(a), Value::Char(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Unit, Value::Unit) => true,
+            (Value::Tuple(a), Value::Tuple(b)) | (Value::List(a), Value::List(b)) => a == b,
+            (Value::Record(a), Value::Record(b)) | (Value::Variant(a), Value::Variant(b)) => a == b,
+            (Value::Function(a, _), Value::Function(b, _))
+            | (Value::Constructor(a, _), Value::Constructor(b, _)) => a == b,
+            (Value::Closure(a), Value::Closure(b)) => {
+                a.id == b.id && a.captures.ptr_eq(&b.captures)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    /// Formats the value as it would be written in source.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn list(f: &mut fmt::Formatter<'_>, values: &[Value]) -> fmt::Result {
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", value)?;
+            }
+            Ok(())
+        }
+
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Char(value) => write!(f, "{:?}", value),
+            Value::String(value) => write!(f, "{:?}", value),
+            Value::Unit => f.write_str("()"),
+            Value::Tuple(elements) => {
+                f.write_str("(")?;
+                list(f, elements)?;
+                if elements.len() == 1 {
+                    f.write_str(",")?;
+                }
+                f.write_str(")")
+            }
+            Value::List(elements) => {
+                f.write_str("[")?;
+                list(f, elements)?;
+                f.write_str("]")
+            }
+            Value::Record(record) => {
+                write!(f, "{} {{ ", record.name)?;
+                for (index, (name, value)) in record.fields.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{} = {}", name, value)?;
+                }
+                f.write_str(" }")
+            }
+            Value::Variant(variant) => {
+                f.write_str(&variant.name)?;
+                if !variant.fields.is_empty() {
+                    f.write_str("(")?;
+                    let values: Vec<_> = variant
+                        .fields
+                        .iter()
+                        .map(|(_, value)| value.clone())
+                        .collect();
+                    list(f, &values)?;
+                    f.write_str(")")?;
+                }
+                Ok(())
+            }
+            Value::Function(_, name) | Value::Constructor(_, name) => {
+                write!(f, "<function {}>", name)
+            }
+            Value::Closure(_) => f.write_str("<closure>"),
+        }
+    }
+}
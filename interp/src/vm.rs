@@ -0,0 +1,488 @@
+//! Execution of bytecode by a stack machine.
+
+use crate::{
+    bytecode::{Capture, Code, Op},
+    error::{Frame, RuntimeError},
+    int::Int,
+    value::{Captures, Closure, Record, Value},
+    MAX_DEPTH,
+};
+use nafi_hir::Span;
+use std::rc::Rc;
+
+/// A call in progress.
+struct CallFrame {
+    /// The number of the call. Each call is numbered after the calls before it.
+    id: u64,
+    /// The index of the called function.
+    function: u32,
+    /// The index of the next instruction.
+    ip: usize,
+    /// The index of the first slot on the stack.
+    base: usize,
+    upvalues: Rc<[Value]>,
+    /// The numbers of the calls of the functions enclosing the called closure, outermost first.
+    calls: Rc<[u64]>,
+    /// Whether the call gave an argument for each parameter, if it did not give them all.
+    given: Option<Rc<[bool]>>,
+    call: Span,
+}
+
+pub(crate) struct Machine<'c> {
+    code: &'c Code,
+    /// The slots and operand stacks of the calls in progress, outermost first.
+    stack: Vec<Value>,
+    frames: Vec<CallFrame>,
+    /// The upvalues of a function declaration.
+    no_upvalues: Rc<[Value]>,
+    /// The enclosing calls of a function declaration.
+    no_calls: Rc<[u64]>,
+    /// The number of the next call.
+    next_call: u64,
+}
+
+impl<'c> Machine<'c> {
+    pub(crate) fn new(code: &'c Code) -> Self {
+        Machine {
+            code,
+            stack: vec![],
+            frames: vec![],
+            no_upvalues: Rc::from(vec![]),
+            no_calls: Rc::from(vec![]),
+            next_call: 0,
+        }
+    }
+
+    /// Run the main function of the program.
+    pub(crate) fn run(&mut self) -> Result<Value, RuntimeError> {
+        let main = &self.code.functions[self.code.main as usize];
+        let span = *main.spans.last().expect("function without instructions");
+        let (upvalues, calls) = (self.no_upvalues.clone(), self.no_calls.clone());
+        self.enter(self.code.main, upvalues, calls, None, 0, span)?;
+        let code = self.code;
+        loop {
+            let frame = self.frames.last_mut().expect("no call is in progress");
+            let chunk = &code.functions[frame.function as usize];
+            let ip = frame.ip;
+            frame.ip += 1;
+            let base = frame.base;
+            let span = chunk.spans[ip];
+            match chunk.code[ip] {
+                Op::Constant(index) => self.stack.push(chunk.constants[index as usize].clone()),
+                Op::Unit => self.stack.push(Value::Unit),
+                Op::True => self.stack.push(Value::Bool(true)),
+                Op::False => self.stack.push(Value::Bool(false)),
+                Op::Slot(slot) => {
+                    let value = self.stack[base + slot as usize].clone();
+                    self.stack.push(value);
+                }
+                Op::SetSlot(slot) => {
+                    let value = self.pop();
+                    self.stack[base + slot as usize] = value;
+                }
+                Op::Upvalue(upvalue) => {
+                    let value = self.frame().upvalues[upvalue as usize].clone();
+                    self.stack.push(value);
+                }
+                Op::Pop => {
+                    self.pop();
+                }
+                Op::SetDepth(depth) => self
+                    .stack
+                    .truncate(base + chunk.slots as usize + depth as usize),
+                Op::Tuple(length) => {
+                    let elements = self.pop_n(length as usize);
+                    self.stack.push(Value::Tuple(Rc::new(elements)));
+                }
+                Op::List(length) => {
+                    let elements = self.pop_n(length as usize);
+                    self.stack.push(Value::List(Rc::new(elements)));
+                }
+                Op::Construct(shape) => {
+                    let shape = &code.shapes[shape as usize];
+                    let values = self.pop_n(shape.fields.len());
+                    let record = Rc::new(Record {
+                        id: shape.id,
+                        name: shape.name.clone(),
+                        fields: shape.fields.iter().cloned().zip(values).collect(),
+                    });
+                    self.stack.push(if shape.variant {
+                        Value::Variant(record)
+                    } else {
+                        Value::Record(record)
+                    });
+                }
+                Op::Closure(function) => {
+                    let function = &code.functions[function as usize];
+                    let upvalues: Vec<_> = function
+                        .captures
+                        .iter()
+                        .map(|capture| match *capture {
+                            Capture::Slot(slot) => self.stack[base + slot as usize].clone(),
+                            Capture::Upvalue(upvalue) => {
+                                self.frame().upvalues[upvalue as usize].clone()
+                            }
+                        })
+                        .collect();
+                    let frame = self.frame();
+                    let calls: Vec<_> = frame.calls.iter().copied().chain(Some(frame.id)).collect();
+                    self.stack.push(Value::Closure(Closure {
+                        id: function.id,
+                        captures: Captures::Upvalues(upvalues.into(), calls.into()),
+                    }));
+                }
+                Op::Element(position) => {
+                    let value = self.pop();
+                    let element = match &value {
+                        Value::Tuple(elements) => elements.get(position as usize),
+                        Value::Record(record) | Value::Variant(record) => {
+                            record.fields.get(position as usize).map(|(_, value)| value)
+                        }
+                        _ => None,
+                    };
+                    match element {
+                        Some(element) => {
+                            let element = element.clone();
+                            self.stack.push(element);
+                        }
+                        None => {
+                            let message = format!("`{}` has no element {}", value, position);
+                            return Err(self.error(span, message));
+                        }
+                    }
+                }
+                Op::Field(name) => {
+                    let name = &chunk.names[name as usize];
+                    let field = match self.pop() {
+                        Value::Record(record) | Value::Variant(record) => match record.get(name) {
+                            Some(value) => value.clone(),
+                            None => {
+                                let message = format!("`{}` has no field `{}`", record.name, name);
+                                return Err(self.error(span, message));
+                            }
+                        },
+                        value => {
+                            let message = format!("`{}` has no field `{}`", value, name);
+                            return Err(self.error(span, message));
+                        }
+                    };
+                    self.stack.push(field);
+                }
+                Op::Index => {
+                    let index = self.pop();
+                    let list = self.pop();
+                    let element = match (&list, &index) {
+                        (Value::List(elements), Value::Int(position)) => {
+                            match position
+                                .to_usize()
+                                .and_then(|position| elements.get(position))
+                            {
+                                Some(element) => element.clone(),
+                                None => {
+                                    let message = format!(
+                                        "index {} is out of bounds of a list of length {}",
+                                        position,
+                                        elements.len(),
+                                    );
+                                    return Err(self.error(span, message));
+                                }
+                            }
+                        }
+                        (Value::List(_), _) => {
+                            let message = format!("cannot index a list with `{}`", index);
+                            return Err(self.error(span, message));
+                        }
+                        _ => {
+                            let message = format!("cannot index into `{}`", list);
+                            return Err(self.error(span, message));
+                        }
+                    };
+                    self.stack.push(element);
+                }
+                Op::Equal => {
+                    let b = self.pop();
+                    let a = self.pop();
+                    self.stack.push(Value::Bool(a == b));
+                }
+                Op::IsShape(shape) => {
+                    let id = code.shapes[shape as usize].id;
+                    let is = match self.pop() {
+                        Value::Record(record) | Value::Variant(record) => record.id == id,
+                        _ => false,
+                    };
+                    self.stack.push(Value::Bool(is));
+                }
+                Op::Jump(target) => self.frame_mut().ip = target as usize,
+                Op::JumpIfFalse(target) => match self.pop() {
+                    Value::Bool(true) => {}
+                    Value::Bool(false) => self.frame_mut().ip = target as usize,
+                    value => {
+                        let message = format!("expected a `Bool`, found `{}`", value);
+                        return Err(self.error(span, message));
+                    }
+                },
+                Op::JumpIfGiven(parameter, target) => {
+                    let frame = self.frame_mut();
+                    if frame
+                        .given
+                        .as_ref()
+                        .is_none_or(|given| given[parameter as usize])
+                    {
+                        frame.ip = target as usize;
+                    }
+                }
+                Op::Iterate(slot) => match self.pop() {
+                    list @ Value::List(_) => {
+                        self.stack[base + slot as usize] = list;
+                        self.stack[base + slot as usize + 1] = Value::Int(Int::from(0_i64));
+                    }
+                    value => {
+                        let message = format!("cannot iterate over `{}`", value);
+                        return Err(self.error(span, message));
+                    }
+                },
+                Op::Next(slot, target) => {
+                    let slot = base + slot as usize;
+                    let position = match &self.stack[slot + 1] {
+                        Value::Int(position) => position.to_usize().expect("iterated too far"),
+                        _ => unreachable!("iterating without a position"),
+                    };
+                    let element = match &self.stack[slot] {
+                        Value::List(elements) => elements.get(position).cloned(),
+                        _ => unreachable!("iterating over a value that is not a list"),
+                    };
+                    match element {
+                        Some(element) => {
+                            self.stack[slot + 1] = Value::Int(Int::from(position + 1));
+                            self.stack.push(element);
+                        }
+                        None => self.frame_mut().ip = target as usize,
+                    }
+                }
+                Op::Call(arguments) => self.call(arguments as usize, span)?,
+                Op::CallDeclared(call) => {
+                    let call = &chunk.calls[call as usize];
+                    let count = call.given.iter().filter(|&&given| given).count();
+                    let given = if count == call.given.len() {
+                        None
+                    } else {
+                        // Lay the arguments out in the slots of the parameters they bind.
+                        let mut arguments = self.pop_n(count).into_iter();
+                        for &given in call.given.iter() {
+                            let value = if given {
+                                arguments.next().expect("fewer arguments than given")
+                            } else {
+                                Value::Unit
+                            };
+                            self.stack.push(value);
+                        }
+                        Some(call.given.clone())
+                    };
+                    let (upvalues, calls) = (self.no_upvalues.clone(), self.no_calls.clone());
+                    self.enter(
+                        call.function,
+                        upvalues,
+                        calls,
+                        given,
+                        call.given.len(),
+                        span,
+                    )?;
+                }
+                Op::Return => {
+                    let value = self.pop();
+                    let frame = self.frames.pop().expect("no call is in progress");
+                    if self.frames.is_empty() {
+                        return Ok(value);
+                    }
+                    self.stack.truncate(frame.base);
+                    self.stack.push(value);
+                }
+                Op::ReturnFrom(level, _) => {
+                    let value = self.pop();
+                    let call = self.frame().calls[level as usize];
+                    let position = match self.frames.binary_search_by_key(&call, |frame| frame.id) {
+                        Ok(position) => position,
+                        Err(_) => {
+                            let message = "cannot return from a function that has already returned";
+                            return Err(self.error(span, message));
+                        }
+                    };
+                    let base = self.frames[position].base;
+                    self.frames.truncate(position);
+                    if self.frames.is_empty() {
+                        return Ok(value);
+                    }
+                    self.stack.truncate(base);
+                    self.stack.push(value);
+                }
+                Op::NoMatch(slot) => {
+                    let value = &self.stack[base + slot as usize];
+                    let message = format!("no arm of this `match` matches `{}`", value);
+                    return Err(self.error(span, message));
+                }
+                Op::Mismatch(slot) => {
+                    let value = &self.stack[base + slot as usize];
+                    let message = format!("`{}` does not match this pattern", value);
+                    return Err(self.error(span, message));
+                }
+                Op::Error(message) => {
+                    let message = chunk.names[message as usize].clone();
+                    return Err(self.error(span, message));
+                }
+                Op::Native(native) => {
+                    let parameters = &self.stack[base..base + chunk.defaults.len()];
+                    match (code.natives[native as usize])(parameters) {
+                        Ok(value) => self.stack.push(value),
+                        Err(message) => {
+                            // Raise the error where the native function was called.
+                            let frame = self.frames.pop().expect("no call is in progress");
+                            return Err(self.error(frame.call, message));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn frame(&self) -> &CallFrame {
+        self.frames.last().expect("no call is in progress")
+    }
+
+    fn frame_mut(&mut self) -> &mut CallFrame {
+        self.frames.last_mut().expect("no call is in progress")
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("popped an empty stack")
+    }
+
+    fn pop_n(&mut self, count: usize) -> Vec<Value> {
+        let at = self.stack.len() - count;
+        self.stack.split_off(at)
+    }
+
+    fn error(&self, span: Span, message: impl Into<String>) -> RuntimeError {
+        RuntimeError {
+            span,
+            message: message.into(),
+            stack: self
+                .frames
+                .iter()
+                .skip(1)
+                .map(|frame| Frame {
+                    name: self.code.functions[frame.function as usize].name.clone(),
+                    call: frame.call,
+                })
+                .collect(),
+        }
+    }
+
+    /// Enter a call of a function whose arguments are the values on top of the stack,
+    /// unless too many are already in progress.
+    fn enter(
+        &mut self,
+        function: u32,
+        upvalues: Rc<[Value]>,
+        calls: Rc<[u64]>,
+        given: Option<Rc<[bool]>>,
+        arguments: usize,
+        call: Span,
+    ) -> Result<(), RuntimeError> {
+        if self.frames.len() > MAX_DEPTH {
+            let message = format!("stack overflow: more than {} calls in progress", MAX_DEPTH);
+            return Err(self.error(call, message));
+        }
+        let base = self.stack.len() - arguments;
+        let slots = self.code.functions[function as usize].slots as usize;
+        self.stack.resize(base + slots, Value::Unit);
+        let id = self.next_call;
+        self.next_call += 1;
+        self.frames.push(CallFrame {
+            id,
+            function,
+            ip: 0,
+            base,
+            upvalues,
+            calls,
+            given,
+            call,
+        });
+        Ok(())
+    }
+
+    /// Call the value below the arguments on top of the stack.
+    fn call(&mut self, arguments: usize, span: Span) -> Result<(), RuntimeError> {
+        let callee = self.stack.remove(self.stack.len() - arguments - 1);
+        match callee {
+            Value::Function(id, name) => {
+                let function = self.code.function(id);
+                let defaults = &self.code.functions[function as usize].defaults;
+                if arguments > defaults.len() {
+                    let message = format!(
+                        "`{}` takes at most {} arguments, but {} were given",
+                        name,
+                        defaults.len(),
+                        arguments,
+                    );
+                    return Err(self.error(span, message));
+                }
+                if let Some(missing) = (arguments..defaults.len()).find(|&index| !defaults[index]) {
+                    let message = format!(
+                        "missing an argument for parameter {} of `{}`",
+                        missing + 1,
+                        name,
+                    );
+                    return Err(self.error(span, message));
+                }
+                let given = if arguments == defaults.len() {
+                    None
+                } else {
+                    let given: Vec<_> =
+                        (0..defaults.len()).map(|index| index < arguments).collect();
+                    Some(given.into())
+                };
+                let (upvalues, calls) = (self.no_upvalues.clone(), self.no_calls.clone());
+                self.enter(function, upvalues, calls, given, arguments, span)
+            }
+            Value::Constructor(id, name) => {
+                let shape = &self.code.shapes[self.code.shape(id) as usize];
+                if arguments != shape.fields.len() {
+                    let message = format!(
+                        "`{}` has {} fields, but {} were given",
+                        name,
+                        shape.fields.len(),
+                        arguments,
+                    );
+                    return Err(self.error(span, message));
+                }
+                let values = self.pop_n(arguments);
+                self.stack.push(Value::Variant(Rc::new(Record {
+                    id,
+                    name: name.to_string(),
+                    fields: shape.fields.iter().cloned().zip(values).collect(),
+                })));
+                Ok(())
+            }
+            Value::Closure(Closure {
+                id,
+                captures: Captures::Upvalues(upvalues, calls),
+            }) => {
+                let function = self.code.function(id);
+                let parameters = self.code.functions[function as usize].defaults.len();
+                if arguments != parameters {
+                    let message = format!(
+                        "this closure takes {} arguments, but {} were given",
+                        parameters, arguments,
+                    );
+                    return Err(self.error(span, message));
+                }
+                self.enter(function, upvalues, calls, None, arguments, span)
+            }
+            Value::Closure(_) => unreachable!("tree-walking interpreter closure in the machine"),
+            value => {
+                let message = format!("`{}` is not a function", value);
+                Err(self.error(span, message))
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! Runs programs with both the tree-walking interpreter and the virtual machine,
+//! checking that they compute the same values and raise the same errors.
+
+use nafi_interp::{Engine, Int, RuntimeError, Value};
+use std::{fs, path::Path};
+
+/// Run a script with both engines, and return the result they agree on.
+fn run(source: &str) -> Result<Value, RuntimeError> {
+    let (analysis, _) = nafi_interp::analyze_script(source).expect("the script has no errors");
+    let walked = Engine::Walk.run(&analysis);
+    let executed = Engine::Bytecode.run(&analysis);
+    assert_eq!(walked, executed, "the engines disagree");
+    walked
+}
+
+fn int(value: i64) -> Value {
+    Value::Int(Int::from(value))
+}
+
+/// The message of the error both engines stop a script with, and the calls in progress.
+fn error(source: &str) -> (String, Vec<String>) {
+    let error = run(source).expect_err("the script stopped with an error");
+    let calls = error.stack.into_iter().map(|frame| frame.name).collect();
+    (error.message, calls)
+}
+
+#[test]
+fn bench_programs_agree() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/programs");
+    for entry in fs::read_dir(directory).expect("could not list the programs") {
+        let path = entry.expect("could not list the programs").path();
+        let source = fs::read_to_string(&path).expect("could not read the program");
+        if let Err(error) = run(&source) {
+            panic!("{} stopped with an error: {}", path.display(), error);
+        }
+    }
+}
+
+#[test]
+fn loops_break_and_continue_to_their_labels() {
+    let source = "{
+        function collect(n: Int): List[Tuple[Int, Int]] = {
+            outer@ for a in range(0, n) {
+                for b in range(0, n) {
+                    match (eq(a, b), lt(a, b)) {
+                        (true, _) => continue @outer,
+                        (_, true) => break,
+                        _ => (),
+                    };
+                    match eq(add(a, b), 5) {
+                        true => return [(a, b)],
+                        false => (),
+                    };
+                }
+            };
+            []
+        }
+        function countdown(n: Int): Int = {
+            found@ loop {
+                loop {
+                    match n {
+                        0 => break @found 100,
+                        _ => break,
+                    }
+                };
+                break n
+            }
+        }
+        (collect(5), collect(2), countdown(0), countdown(3))
+    }";
+    let value = run(source).expect("the script runs");
+    assert_eq!(value.to_string(), "([(3, 2)], [], 100, 3)");
+}
+
+#[test]
+fn recursion_agrees() {
+    let source = "{
+        function isEven(n: Int): Bool = {
+            match n {
+                0 => true,
+                _ => isOdd(sub(n, 1)),
+            }
+        }
+        function isOdd(n: Int): Bool = {
+            match n {
+                0 => false,
+                _ => isEven(sub(n, 1)),
+            }
+        }
+        function fib(n: Int): Int = {
+            match lt(n, 2) {
+                true => n,
+                false => add(fib(sub(n, 1)), fib(sub(n, 2))),
+            }
+        }
+        function factorial(n: Int): Int = {
+            match n {
+                0 => 1,
+                _ => mul(n, factorial(sub(n, 1))),
+            }
+        }
+        (isEven(1001), fib(15), factorial(25))
+    }";
+    let value = run(source).expect("the script runs");
+    assert_eq!(
+        value.to_string(),
+        "(false, 610, 15511210043330985984000000)",
+    );
+}
+
+#[test]
+fn return_exits_through_closures_and_prelude_calls() {
+    let source = "{
+        function firstOver(xs: List[Int], limit: Int): Int = {
+            fold(xs, 0) { total, x ->
+                match lt(limit, x) {
+                    true => return x,
+                    false => add(total, x),
+                }
+            }
+        }
+        function labelled(xs: List[Int]): List[Int] = {
+            map(xs) { x ->
+                match eq(x, 2) {
+                    true => return @map 20,
+                    false => x,
+                }
+            }
+        }
+        function nested(n: Int): Int = {
+            { -> { -> return n }() }();
+            0
+        }
+        (firstOver([1, 5, 9, 2], 4), firstOver([1, 2], 4), labelled([1, 2, 3]), nested(7))
+    }";
+    let value = run(source).expect("the script runs");
+    assert_eq!(value.to_string(), "(5, 3, [1, 20, 3], 7)");
+}
+
+#[test]
+fn return_from_a_closure_passed_down_exits_the_call_that_created_it() {
+    let source = "{
+        function search(depth: Int, found: Function[Int, Unit]): Int = {
+            for x in range(0, 3) {
+                match (depth, x) {
+                    (0, 2) => found(x),
+                    (0, _) => (),
+                    (1, _) => { search(0, found); () },
+                    _ => { search(sub(depth, 1), { y -> return add(y, mul(depth, 10)) }); () },
+                };
+            };
+            neg(1)
+        }
+        search(2, { y -> () })
+    }";
+    assert_eq!(run(source), Ok(int(22)));
+}
+
+#[test]
+fn closures_capture_their_environment() {
+    let source = "{
+        function adder(n: Int): Function[Int, Int] = { { x -> add(x, n) } }
+        function compose(f: Function[Int, Int], g: Function[Int, Int]): Function[Int, Int] = {
+            { x -> g(f(x)) }
+        }
+        function twice(f: Function[Int, Int]): Function[Int, Int] = { compose(f, f) }
+        (twice(adder(3))(1), map([adder(1), adder(2)]) { f -> f(10) })
+    }";
+    let value = run(source).expect("the script runs");
+    assert_eq!(value.to_string(), "(7, [11, 12])");
+}
+
+#[test]
+fn records_enums_and_strings_agree() {
+    let source = "{
+        type Point = { x: Int, y: Int }
+        enum Shape { Circle(center: Point, r: Int), Empty }
+        function describe(shape: Shape): String = {
+            match shape {
+                Shape::Circle(Point { x = 0, y = 0 }, r) => concat(fromChars(['r', '=']), string(r)),
+                Shape::Circle { center, r } => string(center),
+                Shape::Empty => fromChars(reverse(chars(fromChars(['y', 't', 'p', 'm', 'e'])))),
+            }
+        }
+        (
+            describe(Shape::Circle(Point { x = 0, y = 0 }, 2)),
+            describe(Shape::Circle(Point { x = 1, y = 0 }, 2)),
+            describe(Shape::Empty),
+            Shape::Circle(Point { x = 1, y = 2 }, 3),
+        )
+    }";
+    let value = run(source).expect("the script runs");
+    assert_eq!(
+        value.to_string(),
+        "(\"r=2\", \"Point { x = 1, y = 0 }\", \"empty\", Shape::Circle(Point { x = 1, y = 2 }, 3))",
+    );
+}
+
+#[test]
+fn runtime_errors_agree() {
+    let source = "{
+        function divide(a: Int, b: Int): Int = { div(a, b) }
+        divide(1, 0)
+    }";
+    let (message, calls) = error(source);
+    assert_eq!(message, "division by zero");
+    assert_eq!(calls, ["divide"]);
+
+    let (message, calls) = error("{ map([0, 1, 2]) { i -> [10, 20][i] } }");
+    assert_eq!(message, "index 2 is out of bounds of a list of length 2");
+    assert_eq!(calls[0], "map");
+    assert_eq!(calls.last().map(String::as_str), Some("map"));
+
+    let (message, calls) = error("{ assertEqual(add(1, 1), 3) }");
+    assert_eq!(message, "assertion failed: `2` is not equal to `3`");
+    assert!(calls.is_empty());
+
+    let (message, _) = error("{ slice([1, 2, 3], 2, 1) }");
+    assert_eq!(message, "cannot slice from 2 to 1 of a list of length 3");
+}
+
+#[test]
+fn return_exits_the_call_that_created_the_closure() {
+    let source = "{
+        function f(n: Int, k: Function[Unit]): Int = {
+            match n {
+                0 => { k(); 100 },
+                _ => { f(sub(n, 1), { -> return n }); 200 },
+            }
+        }
+        f(1, { -> () })
+    }";
+    assert_eq!(run(source), Ok(int(1)));
+}
+
+#[test]
+fn return_from_a_call_that_has_returned_is_an_error() {
+    let source = "{
+        function h(n: Int, k: Function[Unit]): Function[Unit] = {
+            match n {
+                0 => { -> return { -> () } },
+                _ => { k(); { -> () } },
+            }
+        }
+        h(1, h(0, { -> () }))
+    }";
+    let error = run(source).expect_err("the return escaped");
+    assert_eq!(
+        error.message,
+        "cannot return from a function that has already returned",
+    );
+    let calls: Vec<_> = error.stack.iter().map(|frame| &frame.name[..]).collect();
+    assert_eq!(calls, ["h", "closure"]);
+}
+
+#[test]
+fn deep_recursion_runs_on_any_thread() {
+    let source = |depth: i64| {
+        format!(
+            "{{
+                function count(n: Int): Int = {{
+                    match n {{
+                        0 => 0,
+                        _ => add(count(sub(n, 1)), 1),
+                    }}
+                }}
+                count({})
+            }}",
+            depth,
+        )
+    };
+    assert_eq!(run(&source(9_999)), Ok(int(9_999)));
+    let error = run(&source(10_000)).expect_err("the recursion is too deep");
+    assert_eq!(
+        error.message,
+        "stack overflow: more than 10000 calls in progress",
+    );
+}
@@ -1,5 +1,10 @@
 use ron::ser::to_string_pretty;
-use std::io::{self, prelude::*};
+use std::{
+    env,
+    error::Error,
+    io::{self, prelude::*},
+    process,
+};
 
 fn run() -> io::Result<()> {
     let stdin = io::stdin();
@@ -36,6 +41,25 @@ fn run() -> io::Result<()> {
     }
 }
 
+/// Parse the project rooted at `path` and print each of its source files.
+fn load(path: &str) -> Result<(), Box<dyn Error>> {
+    let sources = ::nafi_parser::Sources::default();
+    let project = ::nafi_parser::Project::load(path, &sources)?;
+    for (file, parse) in project.files().iter().zip(project.trees()) {
+        println!("// {}", file.path().display());
+        println!("{}", to_string_pretty(&parse, Default::default())?);
+    }
+    Ok(())
+}
+
 fn main() {
-    run().unwrap()
+    match env::args().nth(1) {
+        Some(path) => {
+            if let Err(err) = load(&path) {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        }
+        None => run().unwrap(),
+    }
 }
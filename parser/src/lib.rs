@@ -1,5 +1,10 @@
 pub use nafi_ast as ast;
 
+mod project;
+mod validate;
+
+pub use crate::project::{Project, SourceFile, Sources};
+
 pub fn parse(s: &str) -> Result<ast::functions::FunctionExpression, Box<dyn std::error::Error>> {
     use crate::ast::parser::{Parser, Rule};
     use from_pest::FromPest;
@@ -7,5 +12,16 @@ pub fn parse(s: &str) -> Result<ast::functions::FunctionExpression, Box<dyn std:
 
     let mut parse = Parser::parse(Rule::FunctionExpression, s)?;
     assert!(s[parse.as_str().len()..].chars().all(|c| c.is_whitespace()));
+    validate::check_control_flow(parse.clone())?;
+    Ok(FromPest::from_pest(&mut parse).expect("infallible"))
+}
+
+pub fn parse_module(s: &str) -> Result<ast::modules::ModuleFile<'_>, Box<dyn std::error::Error>> {
+    use crate::ast::parser::{Parser, Rule};
+    use from_pest::FromPest;
+    use pest::Parser as Parse;
+
+    let mut parse = Parser::parse(Rule::ModuleFile, s)?;
+    validate::check_control_flow(parse.clone())?;
     Ok(FromPest::from_pest(&mut parse).expect("infallible"))
 }
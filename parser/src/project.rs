@@ -0,0 +1,213 @@
+//! Loading of projects whose modules span several source files.
+
+use crate::{
+    ast::{
+        modules::ModuleFile,
+        parser::{Parser, Rule},
+    },
+    validate,
+};
+use from_pest::FromPest;
+use pest::{
+    error::{Error as PestError, ErrorVariant},
+    iterators::{Pair, Pairs},
+    Parser as Parse,
+};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+use typed_arena::Arena;
+
+/// The file extension of Nafi source files.
+const EXTENSION: &str = "nafi";
+
+/// Holds the source files of projects, which their parse trees borrow.
+#[derive(Default)]
+pub struct Sources {
+    files: Arena<SourceFile>,
+}
+
+/// A project: a root source file and the source files of every module it declares.
+#[derive(Clone, Debug)]
+pub struct Project<'s> {
+    files: Vec<&'s SourceFile>,
+    trees: Vec<ModuleFile<'s>>,
+}
+
+/// A single source file in a project.
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    path: PathBuf,
+    module_path: Vec<String>,
+    source: String,
+}
+
+impl<'s> Project<'s> {
+    /// Load and parse the project rooted at the given file, keeping its source files in `sources`.
+    ///
+    /// A module declared without a body, `module name;`, is loaded from the file `name.nafi`.
+    /// Modules declared in the root file are looked up beside it; modules declared in
+    /// (or inside of) the module `name` are looked up in the directory `name`.
+    pub fn load(root: impl AsRef<Path>, sources: &'s Sources) -> Result<Self, Box<dyn Error>> {
+        let root = root.as_ref();
+        let directory = root.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+        let mut project = Project {
+            files: vec![],
+            trees: vec![],
+        };
+        let mut pending = VecDeque::new();
+        pending.push_back((SourceFile::read(root.to_path_buf(), vec![])?, directory));
+        while let Some((file, directory)) = pending.pop_front() {
+            let file = &*sources.files.alloc(file);
+            let (tree, module_files) = file.parse(&directory)?;
+            for module_file in module_files {
+                let directory = module_file.path.with_extension("");
+                pending.push_back((module_file, directory));
+            }
+            project.files.push(file);
+            project.trees.push(tree);
+        }
+        Ok(project)
+    }
+
+    /// The root source file of the project.
+    pub fn root(&self) -> &'s SourceFile {
+        self.files[0]
+    }
+
+    /// All source files of the project, starting with the root.
+    pub fn files(&self) -> &[&'s SourceFile] {
+        &self.files
+    }
+
+    /// The parse tree of each source file, in the order of [`files`](Project::files).
+    pub fn trees(&self) -> &[ModuleFile<'s>] {
+        &self.trees
+    }
+}
+
+impl SourceFile {
+    fn read(path: PathBuf, module_path: Vec<String>) -> Result<SourceFile, Box<dyn Error>> {
+        let source =
+            fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Ok(SourceFile {
+            path,
+            module_path,
+            source,
+        })
+    }
+
+    /// Parse this file as the body of its module, and read the files of the modules
+    /// it declares without a body from the directory.
+    fn parse(&self, directory: &Path) -> Result<(ModuleFile<'_>, Vec<SourceFile>), Box<dyn Error>> {
+        let mut parse =
+            Parser::parse(Rule::ModuleFile, &self.source).map_err(|err| self.locate(err))?;
+        validate::check_control_flow(parse.clone()).map_err(|err| self.locate(err))?;
+        let mut declared = vec![];
+        find_module_files(parse.clone(), directory, &self.module_path, &mut declared);
+        let mut module_files = vec![];
+        for declaration in declared {
+            let path = declaration
+                .directory
+                .join(&declaration.name)
+                .with_extension(EXTENSION);
+            if !path.is_file() {
+                let message = format!(
+                    "file not found for module `{}`: expected `{}`",
+                    declaration.name,
+                    path.display()
+                );
+                let span = pest::Span::new(&self.source, declaration.start, declaration.end)
+                    .expect("span of a module declaration");
+                let err = PestError::new_from_span(ErrorVariant::CustomError { message }, span);
+                return Err(self.locate(err).into());
+            }
+            let mut module_path = declaration.module_path;
+            module_path.push(declaration.name);
+            module_files.push(SourceFile::read(path, module_path)?);
+        }
+        let tree = ModuleFile::from_pest(&mut parse).expect("infallible");
+        Ok((tree, module_files))
+    }
+
+    /// The location of this file on disk.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The names of the modules leading to the module this file is the body of.
+    ///
+    /// This is empty for the root file.
+    pub fn module_path(&self) -> &[String] {
+        &self.module_path
+    }
+
+    /// The contents of this file.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn locate(&self, err: PestError<Rule>) -> PestError<Rule> {
+        err.with_path(&self.path.to_string_lossy())
+    }
+}
+
+/// A module declared without a body, whose contents live in another file.
+struct ModuleFileDeclaration {
+    name: String,
+    directory: PathBuf,
+    module_path: Vec<String>,
+    start: usize,
+    end: usize,
+}
+
+fn find_module_files(
+    pairs: Pairs<'_, Rule>,
+    directory: &Path,
+    module_path: &[String],
+    declared: &mut Vec<ModuleFileDeclaration>,
+) {
+    for pair in pairs {
+        if pair.as_rule() == Rule::ModuleDeclaration {
+            find_in_module(pair, directory, module_path, declared);
+        } else {
+            find_module_files(pair.into_inner(), directory, module_path, declared);
+        }
+    }
+}
+
+fn find_in_module(
+    pair: Pair<'_, Rule>,
+    directory: &Path,
+    module_path: &[String],
+    declared: &mut Vec<ModuleFileDeclaration>,
+) {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner();
+    let name = inner
+        .find(|inner| inner.as_rule() == Rule::Identifier)
+        .expect("module without a name")
+        .as_str();
+    match inner.next() {
+        Some(body) => {
+            let mut module_path = module_path.to_vec();
+            module_path.push(name.to_string());
+            find_module_files(
+                body.into_inner(),
+                &directory.join(name),
+                &module_path,
+                declared,
+            );
+        }
+        None => declared.push(ModuleFileDeclaration {
+            name: name.to_string(),
+            directory: directory.to_path_buf(),
+            module_path: module_path.to_vec(),
+            start: span.start(),
+            end: span.end(),
+        }),
+    }
+}
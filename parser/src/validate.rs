@@ -0,0 +1,259 @@
+//! Checks on the parse tree that the grammar alone cannot express.
+
+use crate::ast::parser::Rule;
+use pest::{
+    error::{Error, ErrorVariant},
+    iterators::{Pair, Pairs},
+};
+
+/// A loop that `break` and `continue` may refer to.
+struct Loop<'i> {
+    label: Option<&'i str>,
+    accepts_value: bool,
+}
+
+/// The enclosing constructs that control flow expressions may exit.
+#[derive(Default)]
+struct Context<'i> {
+    /// Loops in the innermost function expression.
+    loops: Vec<Loop<'i>>,
+    /// Labels of enclosing function expressions, if they have one.
+    functions: Vec<Option<&'i str>>,
+}
+
+/// Check that every `break`, `continue`, and `return` refers to an enclosing construct.
+///
+/// Function expressions start a new context for loops; they cannot exit loops they are nested in.
+/// Modules start an entirely new context.
+/// A function expression is labelled either explicitly or, as a trailing closure,
+/// by the name of the function or method it is passed to.
+pub(crate) fn check_control_flow(pairs: Pairs<'_, Rule>) -> Result<(), Error<Rule>> {
+    let mut context = Context::default();
+    for pair in pairs {
+        check_pair(pair, &mut context)?;
+    }
+    Ok(())
+}
+
+fn check_pair<'i>(pair: Pair<'i, Rule>, context: &mut Context<'i>) -> Result<(), Error<Rule>> {
+    check_argument_order(&pair)?;
+    if pair.as_rule() == Rule::FunctionDeclaration {
+        check_variadic_arguments(&pair)?;
+    }
+    match pair.as_rule() {
+        Rule::FunctionExpression => check_function(pair, None, context),
+        // Modules are not evaluated in the scope they are declared in.
+        Rule::ModuleBody => check_control_flow(pair.into_inner()),
+        Rule::FunctionCall | Rule::MethodCallPostfix => {
+            let mut name = None;
+            for inner in pair.into_inner() {
+                match inner.as_rule() {
+                    Rule::Path if name.is_none() => {
+                        name = Some(last_segment_name(inner.clone()));
+                        check_pair(inner, context)?;
+                    }
+                    Rule::Identifier if name.is_none() => name = Some(inner.as_str()),
+                    Rule::FunctionExpression => check_function(inner, name, context)?,
+                    _ => check_pair(inner, context)?,
+                }
+            }
+            Ok(())
+        }
+        Rule::WhileExpression | Rule::LoopExpression | Rule::ForExpression => {
+            let accepts_value = pair.as_rule() == Rule::LoopExpression;
+            let mut label = None;
+            for inner in pair.into_inner() {
+                match inner.as_rule() {
+                    Rule::Label => label = Some(label_name(inner)),
+                    Rule::Block => {
+                        context.loops.push(Loop {
+                            label,
+                            accepts_value,
+                        });
+                        let result = check_pair(inner, context);
+                        context.loops.pop();
+                        result?;
+                    }
+                    _ => check_pair(inner, context)?,
+                }
+            }
+            Ok(())
+        }
+        Rule::BreakExpression | Rule::ContinueExpression => {
+            let keyword = if pair.as_rule() == Rule::BreakExpression {
+                "break"
+            } else {
+                "continue"
+            };
+            let span = keyword_span(&pair);
+            let (label, value) = label_and_value(pair);
+            let target = match &label {
+                Some(reference) => {
+                    let name = label_name(reference.clone());
+                    match context.loops.iter().rev().find(|l| l.label == Some(name)) {
+                        Some(target) => target,
+                        None => return Err(undeclared_label(reference)),
+                    }
+                }
+                None => match context.loops.last() {
+                    Some(target) => target,
+                    None => {
+                        return Err(error(format!("`{}` outside of a loop", keyword), span));
+                    }
+                },
+            };
+            if let Some(value) = value {
+                if !target.accepts_value {
+                    return Err(error(
+                        "`break` with a value is only allowed in `loop`".to_string(),
+                        value.as_span(),
+                    ));
+                }
+                check_pair(value, context)?;
+            }
+            Ok(())
+        }
+        Rule::ReturnExpression => {
+            let span = keyword_span(&pair);
+            let (label, value) = label_and_value(pair);
+            if let Some(reference) = label {
+                let name = label_name(reference.clone());
+                if !context.functions.contains(&Some(name)) {
+                    return Err(undeclared_label(&reference));
+                }
+            } else if context.functions.is_empty() {
+                return Err(error("`return` outside of a function".to_string(), span));
+            }
+            if let Some(value) = value {
+                check_pair(value, context)?;
+            }
+            Ok(())
+        }
+        _ => {
+            for inner in pair.into_inner() {
+                check_pair(inner, context)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn check_function<'i>(
+    pair: Pair<'i, Rule>,
+    implicit_label: Option<&'i str>,
+    context: &mut Context<'i>,
+) -> Result<(), Error<Rule>> {
+    let mut inner = pair.into_inner().peekable();
+    let label = match inner.peek() {
+        Some(label) if label.as_rule() == Rule::Label => {
+            Some(label_name(inner.next().expect("peeked")))
+        }
+        _ => implicit_label,
+    };
+    let loops = std::mem::take(&mut context.loops);
+    context.functions.push(label);
+    let result = inner.try_for_each(|pair| check_pair(pair, context));
+    context.functions.pop();
+    context.loops = loops;
+    result
+}
+
+/// Check that no positional argument follows a named argument in a call.
+fn check_argument_order(pair: &Pair<'_, Rule>) -> Result<(), Error<Rule>> {
+    let mut named = false;
+    for argument in pair.clone().into_inner() {
+        if argument.as_rule() != Rule::FunctionCallArgument {
+            continue;
+        }
+        let first = argument
+            .clone()
+            .into_inner()
+            .next()
+            .expect("argument without a value");
+        if first.as_rule() == Rule::Identifier {
+            named = true;
+        } else if named {
+            return Err(error(
+                "positional argument after a named argument".to_string(),
+                argument.as_span(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check that a function declaration has at most one variadic argument, which is not after `*`.
+fn check_variadic_arguments(pair: &Pair<'_, Rule>) -> Result<(), Error<Rule>> {
+    let mut seen = false;
+    for inner in pair.clone().into_inner() {
+        let (arguments, keyword_only) = match inner.as_rule() {
+            Rule::FunctionDeclarationArgument => (vec![inner], false),
+            Rule::KeywordOnlyArguments => (inner.into_inner().collect(), true),
+            _ => continue,
+        };
+        for argument in arguments {
+            match argument.clone().into_inner().next() {
+                Some(first) if first.as_rule() == Rule::Variadic => {}
+                _ => continue,
+            }
+            if keyword_only {
+                let message = "variadic argument after `*`".to_string();
+                return Err(error(message, argument.as_span()));
+            }
+            if seen {
+                let message = "a function may have at most one variadic argument".to_string();
+                return Err(error(message, argument.as_span()));
+            }
+            seen = true;
+        }
+    }
+    Ok(())
+}
+
+fn label_and_value(pair: Pair<'_, Rule>) -> (Option<Pair<'_, Rule>>, Option<Pair<'_, Rule>>) {
+    let mut label = None;
+    let mut value = None;
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::LabelReference => label = Some(inner),
+            Rule::Expression => value = Some(inner),
+            _ => {}
+        }
+    }
+    (label, value)
+}
+
+/// The span of the keyword of a `break`, `continue`, or `return` expression.
+fn keyword_span<'i>(pair: &Pair<'i, Rule>) -> pest::Span<'i> {
+    pair.clone()
+        .into_inner()
+        .next()
+        .expect("control flow expression without a keyword")
+        .as_span()
+}
+
+fn label_name(pair: Pair<'_, Rule>) -> &str {
+    pair.into_inner()
+        .find(|inner| inner.as_rule() == Rule::Identifier)
+        .expect("label without a name")
+        .as_str()
+}
+
+fn last_segment_name(path: Pair<'_, Rule>) -> &str {
+    path.into_inner()
+        .last()
+        .and_then(|segment| segment.into_inner().next())
+        .expect("path without a segment")
+        .as_str()
+}
+
+fn undeclared_label(reference: &Pair<'_, Rule>) -> Error<Rule> {
+    error(
+        format!("use of undeclared label `{}`", reference.as_str()),
+        reference.as_span(),
+    )
+}
+
+fn error(message: String, span: pest::Span<'_>) -> Error<Rule> {
+    Error::new_from_span(ErrorVariant::CustomError { message }, span)
+}
@@ -0,0 +1,85 @@
+//! Checks the errors for what the grammar accepts but a program may not contain.
+
+use nafi_parser::ast::parser::Rule;
+use pest::error::{Error, ErrorVariant, InputLocation};
+
+/// The message of the error parsing a script, and the source text it points at.
+fn error(source: &str) -> (String, &str) {
+    let error = nafi_parser::parse(source).expect_err("the script is invalid");
+    let error = error
+        .downcast::<Error<Rule>>()
+        .expect("the error is a parse error");
+    let message = match error.variant {
+        ErrorVariant::CustomError { message } => message,
+        variant => panic!("expected a validation error, found {:?}", variant),
+    };
+    let span = match error.location {
+        InputLocation::Span((start, end)) => &source[start..end],
+        InputLocation::Pos(position) => &source[position..position],
+    };
+    (message, span)
+}
+
+#[test]
+fn break_and_continue_outside_of_a_loop() {
+    assert_eq!(
+        error("{ if true { break; } }"),
+        ("`break` outside of a loop".to_string(), "break"),
+    );
+    assert_eq!(
+        error("{ loop { { x -> continue } } }"),
+        ("`continue` outside of a loop".to_string(), "continue"),
+    );
+}
+
+#[test]
+fn break_with_a_value_outside_of_loop() {
+    assert_eq!(
+        error("{ while true { break 1; } }"),
+        (
+            "`break` with a value is only allowed in `loop`".to_string(),
+            "1",
+        ),
+    );
+}
+
+#[test]
+fn labels_that_are_not_declared() {
+    assert_eq!(
+        error("{ outer@ loop { break @inner; } }"),
+        ("use of undeclared label `@inner`".to_string(), "@inner"),
+    );
+    assert_eq!(
+        error("{ function f() = { outer@ loop { return @outer; } } }"),
+        ("use of undeclared label `@outer`".to_string(), "@outer"),
+    );
+}
+
+#[test]
+fn positional_arguments_after_named_arguments() {
+    assert_eq!(
+        error("{ f(x = 1, 2) }"),
+        (
+            "positional argument after a named argument".to_string(),
+            "2"
+        ),
+    );
+}
+
+#[test]
+fn variadic_arguments_after_another_or_after_star() {
+    assert_eq!(
+        error("{ function f(...xs: List[Int], ...ys: List[Int]) = { } }"),
+        (
+            "a function may have at most one variadic argument".to_string(),
+            "...ys: List[Int]",
+        ),
+    );
+    assert_eq!(
+        error("{ function f(*, ...xs: List[Int]) = { } }"),
+        (
+            "variadic argument after `*`".to_string(),
+            "...xs: List[Int]"
+        ),
+    );
+}